@@ -0,0 +1,100 @@
+//! Time per iteration of the Ant System on random Euclidean instances,
+//! dominated by the construction of the solutions, next to a reference
+//! construction that computes every selection term on the fly. Run with
+//! `cargo bench`.
+
+use ant_system::{AntProps, AntSystem, Mode, NoopObserver};
+use ndarray::Array2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+const ANTS: usize = 10;
+const ITERATIONS: usize = 5;
+
+fn random_instance(no_cities: usize, seed: u64) -> Array2<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let points: Vec<(f64, f64)> = (0..no_cities)
+        .map(|_| (rng.gen_range(0., 1000.), rng.gen_range(0., 1000.)))
+        .collect();
+
+    Array2::from_shape_fn((no_cities, no_cities), |(i, j)| {
+        let (xd, yd) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+        (xd * xd + yd * yd).sqrt()
+    })
+}
+
+/// Builds a solution the way ants did before the choice information was
+/// precomputed: 𝜏^𝛼 𝜂^𝛽 is computed for every candidate and visited cities
+/// are searched for in the path.
+fn reference_construction(ant_system: &AntSystem, rng: &mut StdRng) -> Vec<usize> {
+    let no_cities = ant_system.visibility.shape()[0];
+    let mut visited = vec![ant_system.initial];
+
+    while visited.len() != no_cities {
+        let curr = *visited.last().expect("No cities visited?");
+        let candidates: Vec<(usize, f64)> = (0..no_cities)
+            .filter(|city| !visited.contains(city))
+            .map(|city| {
+                let pheromone = ant_system.pheromones[[curr, city]].powf(ant_system.alpha);
+                let visibility = ant_system.visibility[[curr, city]].powf(ant_system.beta);
+                (city, pheromone * visibility)
+            })
+            .collect();
+
+        let sum: f64 = candidates.iter().map(|&(_, weight)| weight).sum();
+        let rand = rng.gen_range(0., 1.);
+
+        let mut choosen = candidates[candidates.len() - 1].0;
+        let mut acc = 0.0;
+        for &(city, weight) in &candidates {
+            acc += weight / sum;
+            if rand < acc {
+                choosen = city;
+                break;
+            }
+        }
+
+        visited.push(choosen);
+    }
+
+    visited
+}
+
+fn main() {
+    println!("cities  reference (ms)  per iteration (ms)");
+    for &no_cities in &[100, 500, 1000] {
+        let props = AntProps {
+            beta: 2.0,
+            rho: 0.9,
+            mode: Mode::ClosedTour,
+            seed: 42,
+            ..AntProps::new(random_instance(no_cities, 42))
+        };
+
+        let mut ant_system = AntSystem::new(ANTS, 0, props).unwrap();
+
+        // Only the solutions, the reference leaves out the pheromone update.
+        let mut rng = StdRng::seed_from_u64(42);
+        let start = Instant::now();
+        for _ in 0..ITERATIONS * ANTS {
+            reference_construction(&ant_system, &mut rng);
+        }
+        let reference = start.elapsed().as_secs_f64() / ITERATIONS as f64;
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            ant_system
+                .run(&mut NoopObserver)
+                .expect("NoopObserver never fails");
+        }
+        let elapsed = start.elapsed().as_secs_f64() / ITERATIONS as f64;
+
+        println!(
+            "{:>6}  {:>14.3}  {:>18.3}",
+            no_cities,
+            reference * 1e3,
+            elapsed * 1e3
+        );
+    }
+}
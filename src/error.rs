@@ -0,0 +1,52 @@
+use thiserror::Error;
+
+/// Why [`AntSystem::new`](crate::AntSystem::new) rejected its arguments.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("Distance matrix is {rows}x{columns}, it must be square")]
+    NotSquare { rows: usize, columns: usize },
+
+    #[error("Distance matrix has {cities} cities, at least 2 are needed")]
+    TooFewCities { cities: usize },
+
+    #[error("Start city {start} is out of range, there are {cities} cities")]
+    StartOutOfRange { start: usize, cities: usize },
+
+    /// The distance is NaN or negative.
+    #[error("Distance from city {from} to city {to} is {distance}, it must be non negative or infinite for a missing edge")]
+    InvalidDistance {
+        from: usize,
+        to: usize,
+        distance: f64,
+    },
+
+    /// Two different cities are at distance zero, so their visibility is
+    /// infinite.
+    #[error("Distance from city {from} to city {to} is zero")]
+    ZeroDistance { from: usize, to: usize },
+
+    /// The distance differs from the way back but the colony isn't
+    /// asymmetric.
+    #[error("Distance from city {from} to city {to} differs from the way back, the problem is asymmetric")]
+    Asymmetric { from: usize, to: usize },
+
+    #[error("Local search needs symmetric distances")]
+    AsymmetricLocalSearch,
+
+    /// Every edge leaving or entering the city is missing.
+    #[error("City {city} has no edges to or from the other cities")]
+    Isolated { city: usize },
+
+    #[error("The colony has no ants")]
+    NoAnts,
+
+    #[error("Candidate lists must hold at least one city")]
+    NoCandidates,
+
+    #[error("{name} is {value}, it must be {expected}")]
+    InvalidParameter {
+        name: &'static str,
+        value: f64,
+        expected: &'static str,
+    },
+}
@@ -0,0 +1,38 @@
+//! Ant System (ACO) solver for the travelling salesman problem.
+//!
+//! ```
+//! use ant_system::{AntProps, AntSystem, Mode};
+//! use ndarray::arr2;
+//!
+//! let distances = arr2(&[[0.0, 2.0, 9.0], [2.0, 0.0, 6.0], [9.0, 6.0, 0.0]]);
+//! let props = AntProps {
+//!     mode: Mode::ClosedTour,
+//!     seed: 42,
+//!     ..AntProps::new(distances)
+//! };
+//!
+//! let mut ant_system = AntSystem::new(3, 0, props).unwrap();
+//! let best = ant_system.solve(10).unwrap();
+//! assert_eq!(best.cost, 17.0);
+//! ```
+
+pub mod error;
+pub mod local_search;
+pub mod locale;
+pub mod observer;
+pub mod stats;
+pub mod system;
+pub mod termination;
+pub mod tsplib;
+pub mod utils;
+pub mod variant;
+
+pub use crate::error::ValidationError;
+pub use crate::local_search::{LocalSearch, Operator, Target};
+pub use crate::locale::{Catalog, Language};
+pub use crate::observer::{Candidate, Choice, JsonTrace, NoopObserver, Observer, TextTrace};
+pub use crate::stats::{History, IterationStats};
+pub use crate::system::{AntProps, AntSystem, DeadEnd, Mode, Solution};
+pub use crate::termination::Termination;
+pub use crate::utils::{pretty_matrix, CityLabels};
+pub use crate::variant::{ColonyProps, ElitistProps, MaxMinProps, RankProps, Variant};
@@ -0,0 +1,780 @@
+use crate::system::Mode;
+use anyhow::{anyhow, Error};
+use ndarray::Array2;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+const EPSILON: f64 = 1e-9;
+
+/// Improvement heuristic applied to the solutions built by the ants. Every
+/// operator works on neighbour lists with don't-look bits and runs until no
+/// improving move is left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    /// Reverses the path between two edges.
+    TwoOpt,
+    /// Moves a segment of one to three cities, possibly reversed, between two
+    /// other adjacent cities.
+    OrOpt,
+    /// Replaces three edges, covering the reconnections that aren't 2-opt
+    /// moves.
+    ThreeOpt,
+    /// Lin-Kernighan variable depth search built from chained 2-opt moves,
+    /// suited to large instances.
+    LinKernighan,
+}
+
+impl FromStr for Operator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2opt" => Ok(Operator::TwoOpt),
+            "oropt" => Ok(Operator::OrOpt),
+            "3opt" => Ok(Operator::ThreeOpt),
+            "lk" => Ok(Operator::LinKernighan),
+            other => Err(anyhow!(
+                "Unknown local search operator {}, expected 2opt, oropt, 3opt or lk",
+                other
+            )),
+        }
+    }
+}
+
+/// Which solutions of an iteration are improved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    EveryAnt,
+    IterationBest,
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Target::EveryAnt),
+            "best" => Ok(Target::IterationBest),
+            other => Err(anyhow!("Unknown target {}, expected all or best", other)),
+        }
+    }
+}
+
+/// Local search configuration of an [`AntSystem`](crate::AntSystem).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalSearch {
+    /// Operators applied one after the other.
+    pub operators: Vec<Operator>,
+    pub target: Target,
+    /// Length of the neighbour list of every city, only the closest
+    /// `neighbours` cities are considered as new endpoints of a move.
+    pub neighbours: usize,
+}
+
+impl LocalSearch {
+    /// Improves `path` in place, the initial city stays at the front.
+    pub fn improve(
+        &self,
+        path: &mut [usize],
+        distances: &Array2<f64>,
+        neighbours: &[Vec<usize>],
+        mode: Mode,
+    ) {
+        if path.len() < 3 {
+            return;
+        }
+
+        let mut tour = Tour::new(path, mode);
+        for operator in &self.operators {
+            match operator {
+                Operator::TwoOpt => {
+                    run(&mut tour, |tour, a| two_opt(tour, a, distances, neighbours))
+                }
+                Operator::OrOpt => run(&mut tour, |tour, a| or_opt(tour, a, distances, neighbours)),
+                Operator::ThreeOpt => run(&mut tour, |tour, a| {
+                    three_opt(tour, a, distances, neighbours)
+                }),
+                Operator::LinKernighan => lin_kernighan(&mut tour, distances, neighbours),
+            }
+        }
+    }
+}
+
+/// Returns the `k` closest cities to every city, closest first.
+pub fn nearest_neighbours(distances: &Array2<f64>, k: usize) -> Vec<Vec<usize>> {
+    let no_cities = distances.shape()[0];
+
+    (0..no_cities)
+        .map(|city| {
+            let mut others: Vec<_> = (0..no_cities).filter(|&other| other != city).collect();
+            others.sort_by(|&a, &b| {
+                distances[[city, a]]
+                    .partial_cmp(&distances[[city, b]])
+                    .unwrap()
+            });
+            others.truncate(k);
+            others
+        })
+        .collect()
+}
+
+/// A path with the position of every city, so neighbours are found in O(1).
+/// In an open path the last city has no successor and the first one no
+/// predecessor.
+struct Tour<'a> {
+    path: &'a mut [usize],
+    pos: Vec<usize>,
+    closed: bool,
+}
+
+impl<'a> Tour<'a> {
+    fn new(path: &'a mut [usize], mode: Mode) -> Self {
+        let mut pos = vec![0; path.len()];
+        for (i, &city) in path.iter().enumerate() {
+            pos[city] = i;
+        }
+
+        Self {
+            path,
+            pos,
+            closed: mode == Mode::ClosedTour,
+        }
+    }
+
+    fn succ(&self, city: usize) -> Option<usize> {
+        let i = self.pos[city];
+        if i + 1 < self.path.len() {
+            Some(self.path[i + 1])
+        } else if self.closed {
+            Some(self.path[0])
+        } else {
+            None
+        }
+    }
+
+    fn pred(&self, city: usize) -> Option<usize> {
+        let i = self.pos[city];
+        if i > 0 {
+            Some(self.path[i - 1])
+        } else if self.closed {
+            Some(self.path[self.path.len() - 1])
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the edges leaving `x` and `y` by (x, y) and (succ x, succ y),
+    /// reversing the cities in between. The first city never moves.
+    fn two_opt_move(&mut self, x: usize, y: usize) {
+        let (i, j) = if self.pos[x] < self.pos[y] {
+            (self.pos[x], self.pos[y])
+        } else {
+            (self.pos[y], self.pos[x])
+        };
+
+        self.reverse(i + 1, j);
+    }
+
+    /// Moves the `len` cities starting at position `i` right after position
+    /// `k`, which must be outside the segment, reversing them if asked.
+    fn move_segment(&mut self, i: usize, len: usize, k: usize, reversed: bool) {
+        let (start, end) = if k > i {
+            self.path[i..=k].rotate_left(len);
+            (k + 1 - len, k)
+        } else {
+            self.path[k + 1..i + len].rotate_right(len);
+            (k + 1, k + len)
+        };
+
+        if reversed {
+            self.path[start..=end].reverse();
+        }
+
+        self.update_positions(i.min(k + 1), (i + len - 1).max(k));
+    }
+
+    /// Replaces (t1, t2) and (t3, t4) by (t2, t3) and (t4, t1), where t4 lies
+    /// between t2 and t3 coming from t1. Either side of the tour may be the
+    /// one reversed, so the first city can move and the orientation change.
+    fn exchange(&mut self, t1: usize, t2: usize, t3: usize, t4: usize) {
+        debug_assert!(self.succ(t3) == Some(t4) || self.pred(t3) == Some(t4));
+
+        let (from, to) = if self.succ(t1) == Some(t2) {
+            (t2, t4)
+        } else {
+            (t4, t2)
+        };
+
+        let n = self.path.len();
+        let i = self.pos[from];
+        let len = (self.pos[to] + n - i) % n + 1;
+
+        // Reversing the complement gives the same tour, the shorter side is
+        // reversed.
+        let (i, len) = if 2 * len > n {
+            ((i + len) % n, n - len)
+        } else {
+            (i, len)
+        };
+
+        for k in 0..len / 2 {
+            let (x, y) = ((i + k) % n, (i + len - 1 - k) % n);
+            self.path.swap(x, y);
+            self.pos[self.path[x]] = x;
+            self.pos[self.path[y]] = y;
+        }
+    }
+
+    fn reverse(&mut self, i: usize, j: usize) {
+        self.path[i..=j].reverse();
+        self.update_positions(i, j);
+    }
+
+    fn update_positions(&mut self, i: usize, j: usize) {
+        for k in i..=j {
+            self.pos[self.path[k]] = k;
+        }
+    }
+}
+
+fn cost(distances: &Array2<f64>, from: usize, to: Option<usize>) -> f64 {
+    to.map_or(0.0, |to| distances[[from, to]])
+}
+
+/// Applies `step` from every city until none improves. A step returns the
+/// cities whose edges changed, those are looked at again while cities whose
+/// neighbourhood didn't change since they last failed to improve have their
+/// don't-look bit set and stay out of the queue.
+fn run<F>(tour: &mut Tour, mut step: F)
+where
+    F: FnMut(&mut Tour, usize) -> Option<Vec<usize>>,
+{
+    let mut queue: VecDeque<_> = tour.path.iter().cloned().collect();
+    let mut queued = vec![true; tour.path.len()];
+
+    while let Some(a) = queue.pop_front() {
+        queued[a] = false;
+
+        if let Some(touched) = step(tour, a) {
+            for city in touched {
+                if !queued[city] {
+                    queued[city] = true;
+                    queue.push_back(city);
+                }
+            }
+        }
+    }
+}
+
+/// Applies the first improving 2-opt move that adds an edge from `a` to one
+/// of its neighbours, returning the endpoints of the changed edges.
+fn two_opt(
+    tour: &mut Tour,
+    a: usize,
+    distances: &Array2<f64>,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    match tour.succ(a) {
+        Some(sa) => {
+            let removed = distances[[a, sa]];
+            for &c in &neighbours[a] {
+                let added = distances[[a, c]];
+                if added >= removed {
+                    break;
+                }
+
+                let sc = tour.succ(c);
+                if sc == Some(a) {
+                    continue;
+                }
+
+                let delta = added + cost(distances, sa, sc) - removed - cost(distances, c, sc);
+                if delta < -EPSILON {
+                    tour.two_opt_move(a, c);
+                    return Some(
+                        [Some(a), Some(sa), Some(c), sc]
+                            .iter()
+                            .flatten()
+                            .cloned()
+                            .collect(),
+                    );
+                }
+            }
+        }
+        None => {
+            // `a` ends an open path, reversing the tail after a neighbour
+            // replaces a single edge, so no bound on the neighbour applies.
+            for &c in &neighbours[a] {
+                let sc = match tour.succ(c) {
+                    Some(sc) if sc != a => sc,
+                    _ => continue,
+                };
+
+                let delta = distances[[c, a]] - distances[[c, sc]];
+                if delta < -EPSILON {
+                    tour.two_opt_move(c, a);
+                    return Some(vec![a, c, sc]);
+                }
+            }
+        }
+    }
+
+    if let Some(pa) = tour.pred(a) {
+        let removed = distances[[pa, a]];
+        for &c in &neighbours[a] {
+            let added = distances[[a, c]];
+            if added >= removed {
+                break;
+            }
+
+            let pc = match tour.pred(c) {
+                Some(pc) if pc != a => pc,
+                _ => continue,
+            };
+
+            let delta = added + distances[[pa, pc]] - removed - distances[[pc, c]];
+            if delta < -EPSILON {
+                tour.two_opt_move(pa, pc);
+                return Some(vec![a, pa, c, pc]);
+            }
+        }
+    }
+
+    None
+}
+
+/// Looks for an improving move of a segment of up to three cities that
+/// starts or ends at `a`. Segments never include the first city.
+fn or_opt(
+    tour: &mut Tour,
+    a: usize,
+    distances: &Array2<f64>,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    let n = tour.path.len();
+    let pos = tour.pos[a];
+
+    for len in 1..=3 {
+        let mut starts = vec![pos];
+        if len > 1 && pos + 1 >= len {
+            starts.push(pos + 1 - len);
+        }
+
+        for i in starts {
+            if i == 0 || i + len > n {
+                continue;
+            }
+
+            if let Some(touched) = or_opt_segment(tour, i, len, distances, neighbours) {
+                return Some(touched);
+            }
+        }
+    }
+
+    None
+}
+
+/// Tries every insertion of the `len` cities starting at position `i` next to
+/// a neighbour of one of its ends.
+fn or_opt_segment(
+    tour: &mut Tour,
+    i: usize,
+    len: usize,
+    distances: &Array2<f64>,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    let first = tour.path[i];
+    let last = tour.path[i + len - 1];
+    let prev = tour.path[i - 1];
+    let next = tour.succ(last);
+
+    let in_segment = |city: usize| (i..i + len).contains(&tour.pos[city]);
+    let removed =
+        distances[[prev, first]] + cost(distances, last, next) - cost(distances, prev, next);
+
+    for &end in &[first, last] {
+        for &c in &neighbours[end] {
+            if in_segment(c) {
+                continue;
+            }
+
+            // The segment goes between c and its successor or between its
+            // predecessor and c, with `end` next to c.
+            let edges = [(Some(c), tour.succ(c)), (tour.pred(c), Some(c))];
+            for (k, &(from, to)) in edges.iter().enumerate() {
+                let from = match from {
+                    Some(from) if !in_segment(from) => from,
+                    _ => continue,
+                };
+
+                match to {
+                    Some(to) if in_segment(to) => continue,
+                    _ => {}
+                }
+
+                // Cities of the segment placed right after `from` and right
+                // before `to`.
+                let (after, before) = if k == 0 {
+                    (end, if end == first { last } else { first })
+                } else {
+                    (if end == first { last } else { first }, end)
+                };
+
+                let added = distances[[from, after]] + cost(distances, before, to)
+                    - cost(distances, from, to);
+                if added - removed < -EPSILON {
+                    let reversed = after != first;
+                    tour.move_segment(i, len, tour.pos[from], reversed);
+
+                    let mut touched = vec![prev, first, last, from];
+                    touched.extend(next);
+                    touched.extend(to);
+                    return Some(touched);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// The ways of joining the parts of a path cut at three edges that keep the
+/// first part in front and the last part at the end. The path is split in
+/// A = [..=i], B = [i + 1..=j], C = [j + 1..=k] and the rest D.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reconnection {
+    /// A B' C' D
+    ReverseBoth,
+    /// A C B D
+    Swap,
+    /// A C B' D
+    SwapReverseB,
+    /// A C' B D
+    SwapReverseC,
+}
+
+type Edge = (Option<usize>, Option<usize>);
+
+/// Applies the first improving 3-opt move found by the sequential search
+/// from `t1`: remove (t1, t2), add (t2, t3) with t3 a neighbour of t2, remove
+/// (t3, t4), add (t4, t5) with t5 a neighbour of t4, remove (t5, t6) and close
+/// with (t6, t1). Every partial gain must be positive.
+fn three_opt(
+    tour: &mut Tour,
+    t1: usize,
+    distances: &Array2<f64>,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    for t2 in [tour.succ(t1), tour.pred(t1)].iter().flatten().cloned() {
+        let g1 = distances[[t1, t2]];
+        for &t3 in &neighbours[t2] {
+            let g1 = g1 - distances[[t2, t3]];
+            if g1 <= 0.0 {
+                break;
+            }
+
+            for t4 in [tour.succ(t3), tour.pred(t3)].iter().flatten().cloned() {
+                let g2 = g1 + distances[[t3, t4]];
+                for &t5 in &neighbours[t4] {
+                    if distances[[t4, t5]] >= g2 {
+                        break;
+                    }
+
+                    // The end of an open path may be the last cut, the
+                    // closing edge then disappears.
+                    for &t6 in &[tour.succ(t5), tour.pred(t5)] {
+                        if t6.is_none() && tour.pos[t5] + 1 != tour.path.len() {
+                            continue;
+                        }
+
+                        let removed = [(Some(t1), Some(t2)), (Some(t3), Some(t4)), (Some(t5), t6)];
+                        let added = [(Some(t2), Some(t3)), (Some(t4), Some(t5)), (t6, Some(t1))];
+                        if let Some(touched) = three_opt_move(tour, &removed, &added, distances) {
+                            return Some(touched);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Applies the reconnection replacing the `removed` edges by the `added` ones
+/// if there is one and it improves the cost, returning the endpoints of the
+/// changed edges.
+fn three_opt_move(
+    tour: &mut Tour,
+    removed: &[Edge; 3],
+    added: &[Edge; 3],
+    distances: &Array2<f64>,
+) -> Option<Vec<usize>> {
+    let n = tour.path.len();
+
+    // Every removed edge is cut after the position of its first city in the
+    // path, the missing edge after the end of an open path after the last.
+    let mut cuts = [0; 3];
+    for (cut, &edge) in cuts.iter_mut().zip(removed) {
+        *cut = match edge {
+            (Some(x), Some(y)) if tour.succ(x) == Some(y) => tour.pos[x],
+            (Some(x), Some(y)) if tour.succ(y) == Some(x) => tour.pos[y],
+            (Some(_), None) | (None, Some(_)) => n - 1,
+            _ => return None,
+        };
+    }
+
+    cuts.sort_unstable();
+    let [i, j, k] = cuts;
+    if i == j || j == k {
+        return None;
+    }
+
+    let a = Some(tour.path[i]);
+    let b = Some(tour.path[i + 1]);
+    let c = Some(tour.path[j]);
+    let d = Some(tour.path[j + 1]);
+    let e = Some(tour.path[k]);
+    let f = tour.succ(tour.path[k]);
+
+    let cases = [
+        (Reconnection::ReverseBoth, [(a, c), (b, e), (d, f)]),
+        (Reconnection::Swap, [(a, d), (e, b), (c, f)]),
+        (Reconnection::SwapReverseB, [(a, d), (e, c), (b, f)]),
+        (Reconnection::SwapReverseC, [(a, e), (d, b), (c, f)]),
+    ];
+
+    let same_edge = |x: &Edge, y: &Edge| x == y || (x.1, x.0) == *y;
+    let case = cases
+        .iter()
+        .find(|(_, edges)| {
+            added
+                .iter()
+                .all(|edge| edges.iter().any(|other| same_edge(edge, other)))
+        })
+        .map(|&(case, _)| case)?;
+
+    let length = |edges: &[Edge; 3]| -> f64 {
+        edges
+            .iter()
+            .map(|&(x, y)| match (x, y) {
+                (Some(x), Some(y)) => distances[[x, y]],
+                _ => 0.0,
+            })
+            .sum()
+    };
+
+    // Missing edges can make the gain NaN, that isn't an improvement either.
+    let delta = length(added) - length(removed);
+    if delta.is_nan() || delta >= -EPSILON {
+        return None;
+    }
+
+    match case {
+        Reconnection::ReverseBoth => {
+            tour.reverse(i + 1, j);
+            tour.reverse(j + 1, k);
+        }
+        Reconnection::Swap => tour.move_segment(i + 1, j - i, k, false),
+        Reconnection::SwapReverseB => tour.move_segment(i + 1, j - i, k, true),
+        Reconnection::SwapReverseC => {
+            tour.move_segment(i + 1, j - i, k, false);
+            tour.reverse(i + 1, i + k - j);
+        }
+    }
+
+    Some([a, b, c, d, e, f].iter().flatten().cloned().collect())
+}
+
+/// Deepest chain of 2-opt moves tried from a single city.
+const MAX_DEPTH: usize = 50;
+
+/// Distances of the closed tour searched by the Lin-Kernighan heuristic. An
+/// open path becomes a tour through a dummy city at no distance from every
+/// other, the edge from the dummy to the first city is never removed.
+struct Costs<'a> {
+    distances: &'a Array2<f64>,
+    dummy: Option<usize>,
+    first: usize,
+}
+
+impl Costs<'_> {
+    fn get(&self, x: usize, y: usize) -> f64 {
+        if Some(x) == self.dummy || Some(y) == self.dummy {
+            0.0
+        } else {
+            self.distances[[x, y]]
+        }
+    }
+
+    fn is_fixed(&self, x: usize, y: usize) -> bool {
+        match self.dummy {
+            Some(dummy) => (x, y) == (dummy, self.first) || (y, x) == (dummy, self.first),
+            None => false,
+        }
+    }
+}
+
+/// Runs the Lin-Kernighan search on a closed copy of `tour` and writes the
+/// result back with the first city in front.
+fn lin_kernighan(tour: &mut Tour, distances: &Array2<f64>, neighbours: &[Vec<usize>]) {
+    let n = tour.path.len();
+    let first = tour.path[0];
+    let mut ring = tour.path.to_vec();
+    let dummy = if tour.closed {
+        None
+    } else {
+        ring.push(n);
+        Some(n)
+    };
+
+    let costs = Costs {
+        distances,
+        dummy,
+        first,
+    };
+
+    let mut inner = Tour::new(&mut ring, Mode::ClosedTour);
+    run(&mut inner, |inner, t1| {
+        lk_step(inner, t1, &costs, neighbours)
+    });
+
+    // Bring the first city back to the front, with the dummy at the end.
+    let start = inner.pos[first];
+    ring.rotate_left(start);
+    if dummy.is_some() && ring[1] == n {
+        ring[1..].reverse();
+    }
+
+    tour.path.copy_from_slice(&ring[..n]);
+    tour.update_positions(0, n - 1);
+}
+
+/// Grows chains of 2-opt moves from `t1`: the edge (t1, t2) is removed and
+/// at every level the free end t2 joins a neighbour t3, whose edge to t4 is
+/// removed so t4 becomes the new free end. While the running gain stays
+/// positive every candidate is tried at the first level and the one
+/// maximizing d(t3, t4) - d(t2, t3) at deeper ones, edges added in the chain
+/// are never removed again. A chain is cut back to the level where closing
+/// (t4, t1) gained the most, the first one that gains anything is applied.
+fn lk_step(
+    tour: &mut Tour,
+    t1: usize,
+    costs: &Costs,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    for start in [tour.succ(t1), tour.pred(t1)].iter().flatten().cloned() {
+        if costs.is_fixed(t1, start) {
+            continue;
+        }
+
+        let gain = costs.get(t1, start);
+        for first in lk_candidates(tour, t1, start, gain, &[], costs, neighbours) {
+            if let Some(touched) = lk_chain(tour, t1, start, first, costs, neighbours) {
+                return Some(touched);
+            }
+        }
+    }
+
+    None
+}
+
+/// Moves that continue a chain from the free end `t2`, with the gain after
+/// each one, best first.
+fn lk_candidates(
+    tour: &Tour,
+    t1: usize,
+    t2: usize,
+    gain: f64,
+    added: &[(usize, usize)],
+    costs: &Costs,
+    neighbours: &[Vec<usize>],
+) -> Vec<(f64, usize, usize)> {
+    let forward = tour.succ(t1) == Some(t2);
+    let candidates = match &costs.dummy {
+        Some(dummy) if t2 != *dummy => neighbours[t2].iter().chain(Some(dummy)),
+        _ if t2 < neighbours.len() => neighbours[t2].iter().chain(None),
+        _ => return Vec::new(),
+    };
+
+    let mut moves = Vec::new();
+    for &t3 in candidates {
+        // Missing edges make the gain infinite or NaN, they can't be chained.
+        let g1 = gain - costs.get(t2, t3);
+        if !g1.is_finite() || g1 <= 0.0 {
+            continue;
+        }
+
+        let t4 = if forward {
+            tour.pred(t3)
+        } else {
+            tour.succ(t3)
+        }
+        .expect("Tour is closed");
+
+        if t3 == t1 || t4 == t2 || costs.is_fixed(t3, t4) || added.contains(&edge(t3, t4)) {
+            continue;
+        }
+
+        moves.push((g1 + costs.get(t3, t4), t3, t4));
+    }
+
+    moves.sort_by(|a, b| b.0.total_cmp(&a.0));
+    moves
+}
+
+/// Applies the chain starting with `first` and keeps its best prefix,
+/// returning the endpoints of the changed edges if it improves.
+fn lk_chain(
+    tour: &mut Tour,
+    t1: usize,
+    mut t2: usize,
+    first: (f64, usize, usize),
+    costs: &Costs,
+    neighbours: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    let mut moves = Vec::new();
+    let mut added = Vec::new();
+    let mut best = (0.0, 0);
+    let mut next = Some(first);
+
+    while let Some((next_gain, t3, t4)) = next {
+        tour.exchange(t1, t2, t3, t4);
+        moves.push((t1, t2, t3, t4));
+        added.push(edge(t2, t3));
+
+        let gain = next_gain;
+        t2 = t4;
+
+        let closed = gain - costs.get(t2, t1);
+        if closed > best.0 + EPSILON {
+            best = (closed, moves.len());
+        }
+
+        next = if moves.len() < MAX_DEPTH {
+            lk_candidates(tour, t1, t2, gain, &added, costs, neighbours)
+                .first()
+                .cloned()
+        } else {
+            None
+        };
+    }
+
+    for &(t1, t2, t3, t4) in moves[best.1..].iter().rev() {
+        tour.exchange(t1, t4, t3, t2);
+    }
+
+    if best.1 == 0 {
+        return None;
+    }
+
+    let touched = moves[..best.1]
+        .iter()
+        .flat_map(|&(t1, t2, t3, t4)| vec![t1, t2, t3, t4])
+        .collect();
+    Some(touched)
+}
+
+fn edge(x: usize, y: usize) -> (usize, usize) {
+    (x.min(y), x.max(y))
+}
@@ -0,0 +1,268 @@
+use anyhow::{anyhow, Error};
+use std::str::FromStr;
+
+/// Language of the text trace.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Language {
+    #[default]
+    Spanish,
+    English,
+}
+
+impl Language {
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Language::Spanish => &SPANISH,
+            Language::English => &ENGLISH,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "es" => Ok(Language::Spanish),
+            "en" => Ok(Language::English),
+            other => Err(anyhow!("Unknown language {}, expected es or en", other)),
+        }
+    }
+}
+
+/// Every phrase of the text trace in one language.
+///
+/// Phrases are labels, the numbers, cities and paths they describe are
+/// written around them by the trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catalog {
+    pub parameters: &'static str,
+    pub ants: &'static str,
+    pub termination: &'static str,
+    pub start_city: &'static str,
+    pub initial_pheromone: &'static str,
+    pub problem: &'static str,
+    pub mode: &'static str,
+    pub open_path: &'static str,
+    pub closed_tour: &'static str,
+    pub variant: &'static str,
+    pub ant_system: &'static str,
+    pub max_min: &'static str,
+    pub colony_system: &'static str,
+    pub elitist: &'static str,
+    pub rank_based: &'static str,
+    pub best_so_far_every: &'static str,
+    pub reinit_after: &'static str,
+    pub never: &'static str,
+    pub elitist_weight: &'static str,
+    pub ranks: &'static str,
+    pub local_search: &'static str,
+    pub none: &'static str,
+    pub two_opt: &'static str,
+    pub or_opt: &'static str,
+    pub three_opt: &'static str,
+    pub lin_kernighan: &'static str,
+    pub local_search_on: &'static str,
+    pub every_ant: &'static str,
+    pub iteration_best: &'static str,
+    pub neighbour_lists: &'static str,
+    pub candidate_lists: &'static str,
+    pub every_city: &'static str,
+    pub parallel_construction: &'static str,
+    pub dead_ends: &'static str,
+    pub discard: &'static str,
+    pub backtrack: &'static str,
+    pub repair: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub seed: &'static str,
+
+    pub iteration: &'static str,
+    pub visibility_matrix: &'static str,
+    pub pheromone_matrix: &'static str,
+    pub ant: &'static str,
+    pub sum: &'static str,
+    pub random_number: &'static str,
+    /// Follows the random number when it's below `q0`.
+    pub greedy_choice: &'static str,
+    pub fallback_choice: &'static str,
+    pub next_city: &'static str,
+    /// Follows the ant number when it reaches a dead end.
+    pub stuck: &'static str,
+    pub discarding: &'static str,
+    pub backtracking: &'static str,
+    pub repairing: &'static str,
+    /// Follows the ant number when it fails at a dead end.
+    pub ant_failed: &'static str,
+    pub ant_path: &'static str,
+    pub cost: &'static str,
+    pub local_search_ant: &'static str,
+    pub pheromone: &'static str,
+    pub best_path: &'static str,
+    pub best_global_path: &'static str,
+    pub with_cost: &'static str,
+    /// Precedes the iteration in which the best path was found.
+    pub found_in_iteration: &'static str,
+    pub stopped_by: &'static str,
+    pub failed_ants: &'static str,
+    pub of: &'static str,
+
+    pub iterations: &'static str,
+    pub seconds_elapsed: &'static str,
+    pub iterations_without_improvement: &'static str,
+    pub branching_factor: &'static str,
+    pub and: &'static str,
+    pub or: &'static str,
+}
+
+pub const SPANISH: Catalog = Catalog {
+    parameters: "Parámetros",
+    ants: "Cantidad de hormigas",
+    termination: "Criterio de parada",
+    start_city: "Ciudad inicial",
+    initial_pheromone: "Feromona inicial",
+    problem: "Problema",
+    mode: "Modo",
+    open_path: "camino abierto",
+    closed_tour: "ciclo cerrado",
+    variant: "Variante",
+    ant_system: "Sistema de Hormigas",
+    max_min: "Sistema de Hormigas Max-Min",
+    colony_system: "Sistema de Colonia de Hormigas",
+    elitist: "Sistema de Hormigas Elitista",
+    rank_based: "Sistema de Hormigas basado en rangos",
+    best_so_far_every: "Deposita la mejor global cada",
+    reinit_after: "Reinicio de feromonas tras",
+    never: "nunca",
+    elitist_weight: "Peso de la mejor global (e)",
+    ranks: "Rangos (w)",
+    local_search: "Búsqueda local",
+    none: "ninguna",
+    two_opt: "2-opt",
+    or_opt: "Or-opt",
+    three_opt: "3-opt",
+    lin_kernighan: "Lin-Kernighan",
+    local_search_on: "Búsqueda local sobre",
+    every_ant: "todas las hormigas",
+    iteration_best: "la mejor de la iteración",
+    neighbour_lists: "Lista de vecinos",
+    candidate_lists: "Lista de candidatos",
+    every_city: "todas las ciudades",
+    parallel_construction: "Construcción en paralelo",
+    dead_ends: "Sin salida",
+    discard: "descartar la hormiga",
+    backtrack: "retroceder",
+    repair: "insertar las ciudades restantes",
+    yes: "sí",
+    no: "no",
+    seed: "Semilla",
+
+    iteration: "Iteración",
+    visibility_matrix: "Matriz de visibilidad",
+    pheromone_matrix: "Matriz de feromonas",
+    ant: "Hormiga",
+    sum: "Suma",
+    random_number: "Número aleatorio",
+    greedy_choice: "< q0, se elige la ciudad de mayor peso",
+    fallback_choice: "Candidatos visitados, se elige la ciudad restante de mayor peso",
+    next_city: "Siguiente ciudad",
+    stuck: "sin salida",
+    discarding: "se descarta",
+    backtracking: "retrocede",
+    repairing: "se insertan las ciudades restantes",
+    ant_failed: "no completó su solución",
+    ant_path: "Camino de la hormiga",
+    cost: "costo",
+    local_search_ant: "Búsqueda local, hormiga",
+    pheromone: "feromona",
+    best_path: "Mejor camino",
+    best_global_path: "Mejor camino global",
+    with_cost: "con costo",
+    found_in_iteration: "iteración",
+    stopped_by: "Detenido por",
+    failed_ants: "Hormigas fallidas",
+    of: "de",
+
+    iterations: "iteraciones",
+    seconds_elapsed: "s transcurridos",
+    iterations_without_improvement: "iteraciones sin mejora",
+    branching_factor: "factor de ramificación λ",
+    and: "y",
+    or: "o",
+};
+
+pub const ENGLISH: Catalog = Catalog {
+    parameters: "Parameters",
+    ants: "Number of ants",
+    termination: "Stopping criterion",
+    start_city: "Start city",
+    initial_pheromone: "Initial pheromone",
+    problem: "Problem",
+    mode: "Mode",
+    open_path: "open path",
+    closed_tour: "closed tour",
+    variant: "Variant",
+    ant_system: "Ant System",
+    max_min: "Max-Min Ant System",
+    colony_system: "Ant Colony System",
+    elitist: "Elitist Ant System",
+    rank_based: "Rank-based Ant System",
+    best_so_far_every: "Best-so-far deposits every",
+    reinit_after: "Trails reset after",
+    never: "never",
+    elitist_weight: "Best-so-far weight (e)",
+    ranks: "Ranks (w)",
+    local_search: "Local search",
+    none: "none",
+    two_opt: "2-opt",
+    or_opt: "Or-opt",
+    three_opt: "3-opt",
+    lin_kernighan: "Lin-Kernighan",
+    local_search_on: "Local search on",
+    every_ant: "every ant",
+    iteration_best: "the iteration best",
+    neighbour_lists: "Neighbour list",
+    candidate_lists: "Candidate list",
+    every_city: "every city",
+    parallel_construction: "Parallel construction",
+    dead_ends: "Dead ends",
+    discard: "discard the ant",
+    backtrack: "backtrack",
+    repair: "insert the remaining cities",
+    yes: "yes",
+    no: "no",
+    seed: "Seed",
+
+    iteration: "Iteration",
+    visibility_matrix: "Visibility matrix",
+    pheromone_matrix: "Pheromone matrix",
+    ant: "Ant",
+    sum: "Sum",
+    random_number: "Random number",
+    greedy_choice: "< q0, the city with the largest weight is taken",
+    fallback_choice: "Candidates visited, the remaining city with the largest weight is taken",
+    next_city: "Next city",
+    stuck: "is stuck",
+    discarding: "discarding it",
+    backtracking: "backtracking",
+    repairing: "inserting the remaining cities",
+    ant_failed: "failed to complete its solution",
+    ant_path: "Path of ant",
+    cost: "cost",
+    local_search_ant: "Local search, ant",
+    pheromone: "pheromone",
+    best_path: "Best path",
+    best_global_path: "Best global path",
+    with_cost: "with cost",
+    found_in_iteration: "iteration",
+    stopped_by: "Stopped by",
+    failed_ants: "Failed ants",
+    of: "of",
+
+    iterations: "iterations",
+    seconds_elapsed: "s elapsed",
+    iterations_without_improvement: "iterations without improvement",
+    branching_factor: "λ-branching factor",
+    and: "and",
+    or: "or",
+};
@@ -1,105 +1,858 @@
-pub mod system;
-pub mod utils;
-
-use crate::system::{AntProps, AntSystem};
-use crate::utils::{pretty_matrix, ToDisplayPath};
-use anyhow::Error;
-use indicatif::ProgressIterator;
-use ndarray::Array2;
+use ant_system::tsplib::{self, Instance};
+use ant_system::{
+    AntProps, AntSystem, CityLabels, ColonyProps, DeadEnd, ElitistProps, JsonTrace, Language,
+    LocalSearch, MaxMinProps, Mode, NoopObserver, Observer, Operator, RankProps, Target,
+    Termination, TextTrace, Variant,
+};
+use anyhow::{anyhow, bail, Error};
+use indicatif::{ProgressBar, ProgressIterator};
 use prettytable::format::consts::FORMAT_BOX_CHARS;
-use prettytable::{cell, row, table};
+use prettytable::{row, table};
+use rand::{thread_rng, Rng};
+use serde_json::json;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
 
-fn main() -> Result<(), Error> {
-    let distances: Vec<_> = [
-        0, 12, 3, 23, 1, 5, 23, 56, 12, 11, //
-        12, 0, 9, 18, 3, 41, 45, 5, 41, 27, //
-        3, 9, 0, 89, 56, 21, 12, 48, 14, 29, //
-        23, 18, 89, 0, 87, 46, 75, 17, 50, 42, //
-        1, 3, 56, 87, 0, 55, 22, 86, 14, 33, //
-        5, 41, 21, 46, 55, 0, 21, 76, 54, 81, //
-        23, 45, 12, 75, 22, 21, 0, 11, 57, 48, //
-        56, 5, 48, 17, 86, 76, 11, 0, 63, 24, //
-        12, 41, 14, 50, 14, 54, 57, 63, 0, 9, //
-        11, 27, 29, 42, 33, 81, 48, 24, 9, 0, //
-    ]
-    .iter()
-    .map(|v| *v as f64)
-    .collect();
-
-    let distances = Array2::from_shape_vec((10, 10), distances)?;
-
-    let size = 10;
-    let iters = 100;
-
-    let props = AntProps {
-        alpha: 1.0,
-        beta: 1.0,
-        rho: 0.99,
-        q: 1.0,
-        initial_pheromone: 0.1,
-        distances,
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Solves TSP instances with the Ant System algorithm")]
+enum Command {
+    /// Runs the colony and prints the best path found
+    Solve {
+        #[structopt(flatten)]
+        params: Params,
+
+        /// Also write the best path to this file
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+
+        /// Write the statistics of every iteration to this CSV file
+        #[structopt(long)]
+        history: Option<PathBuf>,
+    },
+
+    /// Runs the colony writing a detailed trace of every step
+    Trace {
+        #[structopt(flatten)]
+        params: Params,
+
+        /// Trace destination, use `-` for stdout
+        #[structopt(short, long, default_value = "ant-system.out")]
+        output: PathBuf,
+
+        /// Write the human readable trace, a JSON Lines trace or both
+        #[structopt(long, default_value = "text", possible_values = &["text", "json", "both"])]
+        format: Format,
+
+        /// JSON Lines trace destination, use `-` for stdout
+        #[structopt(long, default_value = "ant-system.jsonl")]
+        json_output: PathBuf,
+
+        /// Language of the text trace, Spanish or English
+        #[structopt(long, default_value = "es", possible_values = &["es", "en"])]
+        lang: Language,
+
+        /// Write the statistics of every iteration to this CSV file
+        #[structopt(long)]
+        history: Option<PathBuf>,
+    },
+
+    /// Runs the colony several times and reports cost and time statistics
+    Bench {
+        #[structopt(flatten)]
+        params: Params,
+
+        /// Number of independent runs
+        #[structopt(long, default_value = "10")]
+        runs: usize,
+
+        /// Also write the report to this file
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+struct Params {
+    /// TSPLIB instance file
+    #[structopt(parse(from_os_str))]
+    instance: PathBuf,
+
+    /// Number of ants
+    #[structopt(short = "m", long, default_value = "10")]
+    ants: usize,
+
+    /// Stop after this many iterations, 100 if no other criterion is given
+    #[structopt(short = "n", long)]
+    iters: Option<usize>,
+
+    /// Stop after this many seconds
+    #[structopt(long)]
+    time_limit: Option<f64>,
+
+    /// Stop once the best cost is at or below this value
+    #[structopt(long)]
+    target_cost: Option<f64>,
+
+    /// Stop after this many iterations without improving the best cost
+    #[structopt(long)]
+    no_improvement: Option<usize>,
+
+    /// Stop once the average λ-branching factor drops to this value
+    #[structopt(long)]
+    stagnation: Option<f64>,
+
+    /// λ used to compute the branching factor
+    #[structopt(long, default_value = "0.05")]
+    lambda: f64,
+
+    /// Stop when any or all of the criteria are met
+    #[structopt(long, default_value = "any", possible_values = &["any", "all"])]
+    stop_when: StopWhen,
+
+    /// Index of the city every ant starts from
+    #[structopt(short, long, default_value = "0")]
+    start: usize,
+
+    /// Pheromone influence (𝛼)
+    #[structopt(long, default_value = "1.0")]
+    alpha: f64,
+
+    /// Visibility influence (𝛽)
+    #[structopt(long, default_value = "1.0")]
+    beta: f64,
+
+    /// Fraction of pheromone kept after each iteration (𝜌)
+    #[structopt(long, default_value = "0.99")]
+    rho: f64,
+
+    /// Pheromone deposited by an ant, divided by its path cost (Q)
+    #[structopt(long, default_value = "1.0")]
+    q: f64,
+
+    /// Pheromone on every edge before the first iteration
+    #[structopt(long, default_value = "0.1")]
+    initial_pheromone: f64,
+
+    /// Whether solutions are open paths or closed tours back to the start city
+    #[structopt(long, default_value = "path", possible_values = &["path", "tour"])]
+    mode: Mode,
+
+    /// Pheromone update rule: Ant System, Max-Min, Ant Colony System, Elitist or
+    /// Rank-based Ant System
+    #[structopt(
+        long,
+        default_value = "as",
+        possible_values = &["as", "mmas", "acs", "eas", "ras"]
+    )]
+    variant: VariantKind,
+
+    /// MMAS: probability of building the best solution at convergence, sets τ_min
+    #[structopt(long, default_value = "0.05")]
+    p_best: f64,
+
+    /// MMAS: the best-so-far solution deposits every this many iterations (0 = never)
+    #[structopt(long, default_value = "0")]
+    best_so_far_every: usize,
+
+    /// MMAS: reset trails after this many iterations without improvement
+    #[structopt(long)]
+    reinit_after: Option<usize>,
+
+    /// ACS: probability of taking the best candidate instead of the roulette
+    #[structopt(long, default_value = "0.9")]
+    q0: f64,
+
+    /// ACS: decay of the local pheromone update (𝜉)
+    #[structopt(long, default_value = "0.1")]
+    xi: f64,
+
+    /// EAS: weight of the best-so-far solution, defaults to the number of cities
+    #[structopt(long)]
+    elitist_weight: Option<f64>,
+
+    /// RAS: number of ranks, the best w - 1 ants and the best-so-far deposit
+    #[structopt(long, default_value = "6")]
+    rank_weight: usize,
+
+    /// Local search operators applied in order to the ant solutions before the
+    /// pheromone update, e.g. `2opt,oropt`
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = "none",
+        possible_values = &["none", "2opt", "oropt", "3opt", "lk"],
+        parse(try_from_str = parse_operator)
+    )]
+    local_search: Vec<Option<Operator>>,
+
+    /// Apply the local search to every ant or only to the iteration best
+    #[structopt(long, default_value = "all", possible_values = &["all", "best"])]
+    local_search_on: Target,
+
+    /// Length of the neighbour lists used by the local search
+    #[structopt(long, default_value = "20")]
+    neighbours: usize,
+
+    /// Length of the candidate lists ants choose from, every unvisited city is a
+    /// candidate if omitted
+    #[structopt(long)]
+    candidates: Option<usize>,
+
+    /// Build the solutions of all ants in parallel, the trace then omits the
+    /// construction steps
+    #[structopt(long)]
+    parallel: bool,
+
+    /// What an ant does when no unvisited city is reachable: fail, go back
+    /// to try another city, or insert the remaining cities where they fit
+    #[structopt(long, default_value = "discard", possible_values = &["discard", "backtrack", "repair"])]
+    dead_end: DeadEnd,
+
+    /// Name cities with letters (A, B, ..., AA, AB, ...), their index from 0
+    /// or the names in the NODE_NAME_SECTION of the instance
+    #[structopt(long, default_value = "letters", possible_values = &["letters", "numbers", "names"])]
+    labels: Labels,
+
+    /// Seed for the random number generator, a random one is used if omitted
+    #[structopt(long)]
+    seed: Option<u64>,
+}
+
+impl Params {
+    fn variant(&self, no_cities: usize) -> Variant {
+        match self.variant {
+            VariantKind::AntSystem => Variant::AntSystem,
+            VariantKind::MaxMin => Variant::MaxMin(MaxMinProps {
+                p_best: self.p_best,
+                best_so_far_every: self.best_so_far_every,
+                reinit_after: self.reinit_after,
+            }),
+            VariantKind::ColonySystem => Variant::ColonySystem(ColonyProps {
+                q0: self.q0,
+                xi: self.xi,
+            }),
+            VariantKind::Elitist => Variant::Elitist(ElitistProps {
+                e: self.elitist_weight.unwrap_or(no_cities as f64),
+            }),
+            VariantKind::RankBased => Variant::RankBased(RankProps {
+                w: self.rank_weight,
+            }),
+        }
+    }
+
+    fn local_search(&self) -> Option<LocalSearch> {
+        let operators: Vec<_> = self.local_search.iter().flatten().cloned().collect();
+        if operators.is_empty() {
+            return None;
+        }
+
+        Some(LocalSearch {
+            operators,
+            target: self.local_search_on,
+            neighbours: self.neighbours,
+        })
+    }
+
+    fn termination(&self) -> Result<Termination, Error> {
+        let mut criteria = Vec::new();
+        criteria.extend(self.iters.map(Termination::Iterations));
+        if let Some(secs) = self.time_limit {
+            match Duration::try_from_secs_f64(secs) {
+                Ok(limit) if secs > 0.0 => criteria.push(Termination::Time(limit)),
+                _ => bail!(
+                    "time_limit is {}, it must be a positive number of seconds",
+                    secs
+                ),
+            }
+        }
+        criteria.extend(self.target_cost.map(Termination::TargetCost));
+        criteria.extend(self.no_improvement.map(Termination::NoImprovement));
+        criteria.extend(self.stagnation.map(|threshold| Termination::Stagnation {
+            lambda: self.lambda,
+            threshold,
+        }));
+
+        Ok(match criteria.len() {
+            0 => Termination::Iterations(100),
+            1 => criteria.remove(0),
+            _ => match self.stop_when {
+                StopWhen::Any => Termination::Any(criteria),
+                StopWhen::All => Termination::All(criteria),
+            },
+        })
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| thread_rng().gen())
+    }
+
+    fn instance(&self) -> Result<Instance, Error> {
+        tsplib::load(&self.instance)
+    }
+
+    fn labels(&self, instance: &Instance) -> Result<CityLabels, Error> {
+        match self.labels {
+            Labels::Letters => Ok(CityLabels::Letters),
+            Labels::Numbers => Ok(CityLabels::Numbers),
+            Labels::Names => match &instance.names {
+                Some(names) => Ok(CityLabels::Names(names.clone())),
+                None => bail!("{} has no NODE_NAME_SECTION", instance.name),
+            },
+        }
+    }
+
+    fn ant_system(&self, instance: &Instance, seed: u64) -> Result<AntSystem, Error> {
+        let props = AntProps {
+            alpha: self.alpha,
+            beta: self.beta,
+            rho: self.rho,
+            q: self.q,
+            initial_pheromone: self.initial_pheromone,
+            distances: instance.distances.clone(),
+            mode: self.mode,
+            asymmetric: instance.asymmetric,
+            variant: self.variant(instance.dimension),
+            local_search: self.local_search(),
+            candidates: self.candidates,
+            parallel: self.parallel,
+            dead_end: self.dead_end,
+            seed,
+        };
+
+        Ok(AntSystem::new(self.ants, self.start, props)?)
+    }
+}
+
+/// Pheromone update rule chosen on the command line, its parameters come from
+/// the options of each variant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VariantKind {
+    AntSystem,
+    MaxMin,
+    ColonySystem,
+    Elitist,
+    RankBased,
+}
+
+impl VariantKind {
+    fn name(self) -> &'static str {
+        match self {
+            VariantKind::AntSystem => "as",
+            VariantKind::MaxMin => "mmas",
+            VariantKind::ColonySystem => "acs",
+            VariantKind::Elitist => "eas",
+            VariantKind::RankBased => "ras",
+        }
+    }
+}
+
+impl FromStr for VariantKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "as" => Ok(VariantKind::AntSystem),
+            "mmas" => Ok(VariantKind::MaxMin),
+            "acs" => Ok(VariantKind::ColonySystem),
+            "eas" => Ok(VariantKind::Elitist),
+            "ras" => Ok(VariantKind::RankBased),
+            other => Err(anyhow!(
+                "Unknown variant {}, expected as, mmas, acs, eas or ras",
+                other
+            )),
+        }
+    }
+}
+
+/// `none` stands for no operator, so `--local-search none` turns it off.
+fn parse_operator(s: &str) -> Result<Option<Operator>, Error> {
+    match s {
+        "none" => Ok(None),
+        operator => operator.parse().map(Some),
+    }
+}
+
+fn operator_name(operator: Option<Operator>) -> &'static str {
+    match operator {
+        None => "none",
+        Some(Operator::TwoOpt) => "2opt",
+        Some(Operator::OrOpt) => "oropt",
+        Some(Operator::ThreeOpt) => "3opt",
+        Some(Operator::LinKernighan) => "lk",
+    }
+}
+
+/// Whether several stopping criteria must all fire or only one of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopWhen {
+    Any,
+    All,
+}
+
+impl FromStr for StopWhen {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(StopWhen::Any),
+            "all" => Ok(StopWhen::All),
+            other => Err(anyhow!(
+                "Unknown criteria combination {}, expected any or all",
+                other
+            )),
+        }
+    }
+}
+
+/// How cities are named, [`CityLabels`] once the instance is read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Labels {
+    Letters,
+    Numbers,
+    Names,
+}
+
+impl FromStr for Labels {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "letters" => Ok(Labels::Letters),
+            "numbers" => Ok(Labels::Numbers),
+            "names" => Ok(Labels::Names),
+            other => Err(anyhow!(
+                "Unknown labels {}, expected letters, numbers or names",
+                other
+            )),
+        }
+    }
+}
+
+/// Which traces the trace command writes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Both,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "both" => Ok(Format::Both),
+            other => Err(anyhow!(
+                "Unknown format {}, expected text, json or both",
+                other
+            )),
+        }
+    }
+}
+
+fn create_output(path: &Path) -> Result<Box<dyn Write>, Error> {
+    if path.as_os_str() == "-" {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Ok(Box::new(File::create(path)?))
+    }
+}
+
+/// Runs `ant_system` until `termination` fires showing the progress.
+fn run_with_progress(
+    ant_system: &mut AntSystem,
+    termination: &Termination,
+    observer: &mut dyn Observer,
+) -> Result<Termination, Error> {
+    let progress = match termination {
+        Termination::Iterations(iterations) => ProgressBar::new(*iterations as u64),
+        _ => ProgressBar::new_spinner(),
     };
 
-    let mut table = table! {
-        ["Cantidad de hormigas", size],
-        ["Cantidad de iteraciones", iters],
-        ["Ciudad inicial", "A"],
-        ["𝛼 (alpha)", props.alpha],
-        ["𝛽 (beta)", props.beta],
-        ["𝜌 (rho)", props.rho],
-        ["Q", props.q],
-        ["Feromona inicial", props.initial_pheromone]
+    let fired = ant_system.run_until_with(termination, observer, |_| progress.inc(1))?;
+    progress.finish_and_clear();
+
+    Ok(fired)
+}
+
+fn write_history(ant_system: &AntSystem, path: Option<&Path>) -> Result<(), Error> {
+    match path {
+        Some(path) => ant_system.history().write_csv(create_output(path)?),
+        None => Ok(()),
+    }
+}
+
+fn solve(params: &Params, output: Option<&Path>, history: Option<&Path>) -> Result<(), Error> {
+    let seed = params.seed();
+    let instance = params.instance()?;
+    let labels = params.labels(&instance)?;
+    let mut ant_system = params.ant_system(&instance, seed)?;
+    let fired = run_with_progress(&mut ant_system, &params.termination()?, &mut NoopObserver)?;
+    write_history(&ant_system, history)?;
+
+    println!("Seed: {}", seed);
+    println!("Stopped by: {}", fired);
+    println!(
+        "Failed ants: {} of {}",
+        ant_system.history().failed_ants(),
+        ant_system.size * ant_system.iteration()
+    );
+
+    let best = match ant_system.best() {
+        Some(best) => best,
+        None => bail!("No ant completed a solution"),
     };
-    table.set_format(*FORMAT_BOX_CHARS);
+    let line = format!(
+        "Best global path: {} with cost {} (iteration {})",
+        labels.path(&best.path),
+        best.cost,
+        best.iteration
+    );
 
-    let mut out = File::create("ant-system.out")?;
+    println!("{}", line);
+    if let Some(path) = output {
+        writeln!(create_output(path)?, "{}", line)?;
+    }
 
-    writeln!(out, "Parámetros")?;
-    writeln!(out, "{}\n", table)?;
+    Ok(())
+}
 
-    let mut ant_system = AntSystem::new(size, 3, props);
-    let mut best = None;
-    for i in (0..iters).progress() {
-        writeln!(out, "------------------------------------")?;
-        writeln!(out, "Iteración {}\n", i + 1)?;
+fn trace(
+    params: &Params,
+    language: Language,
+    output: Option<&Path>,
+    json_output: Option<&Path>,
+    history: Option<&Path>,
+) -> Result<(), Error> {
+    let seed = params.seed();
+    let instance = params.instance()?;
+    let labels = params.labels(&instance)?;
+    let mut ant_system = params.ant_system(&instance, seed)?;
+    let termination = params.termination()?;
 
-        writeln!(
-            out,
-            "Matriz de visibilidad:\n{}",
-            pretty_matrix(&ant_system.visibility, 6)
-        )?;
+    let mut text = None;
+    if let Some(path) = output {
+        let catalog = language.catalog();
+        let mut table = table! {
+            [catalog.ants, params.ants],
+            [catalog.termination, termination.localized(catalog)],
+            [catalog.start_city, labels.label(params.start)],
+            ["𝛼 (alpha)", params.alpha],
+            ["𝛽 (beta)", params.beta],
+            ["𝜌 (rho)", params.rho],
+            ["Q", params.q],
+            [catalog.initial_pheromone, ant_system.initial_pheromone()],
+            [catalog.problem, if instance.asymmetric { "ATSP" } else { "TSP" }],
+            [catalog.mode, match params.mode {
+                Mode::OpenPath => catalog.open_path,
+                Mode::ClosedTour => catalog.closed_tour,
+            }],
+            [catalog.variant, match params.variant {
+                VariantKind::AntSystem => catalog.ant_system,
+                VariantKind::MaxMin => catalog.max_min,
+                VariantKind::ColonySystem => catalog.colony_system,
+                VariantKind::Elitist => catalog.elitist,
+                VariantKind::RankBased => catalog.rank_based,
+            }]
+        };
 
-        writeln!(
-            out,
-            "Matriz de feromonas:\n{}",
-            pretty_matrix(&ant_system.pheromones, 6)
+        // Only the parameters of the variant in use follow it.
+        match &ant_system.variant {
+            Variant::AntSystem => {}
+            Variant::MaxMin(props) => {
+                table.add_row(row!["p_best", props.p_best]);
+                table.add_row(row![
+                    catalog.best_so_far_every,
+                    match props.best_so_far_every {
+                        0 => catalog.never.to_owned(),
+                        every => every.to_string(),
+                    }
+                ]);
+                table.add_row(row![
+                    catalog.reinit_after,
+                    match props.reinit_after {
+                        Some(after) => after.to_string(),
+                        None => catalog.never.to_owned(),
+                    }
+                ]);
+            }
+            Variant::ColonySystem(props) => {
+                table.add_row(row!["q0", props.q0]);
+                table.add_row(row!["𝜉 (xi)", props.xi]);
+            }
+            Variant::Elitist(props) => {
+                table.add_row(row![catalog.elitist_weight, props.e]);
+            }
+            Variant::RankBased(props) => {
+                table.add_row(row![catalog.ranks, props.w]);
+            }
+        }
+
+        table.add_row(row![
+            catalog.local_search,
+            params
+                .local_search
+                .iter()
+                .map(|operator| match operator {
+                    None => catalog.none,
+                    Some(Operator::TwoOpt) => catalog.two_opt,
+                    Some(Operator::OrOpt) => catalog.or_opt,
+                    Some(Operator::ThreeOpt) => catalog.three_opt,
+                    Some(Operator::LinKernighan) => catalog.lin_kernighan,
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ]);
+        if let Some(local_search) = &ant_system.local_search {
+            table.add_row(row![
+                catalog.local_search_on,
+                match local_search.target {
+                    Target::EveryAnt => catalog.every_ant,
+                    Target::IterationBest => catalog.iteration_best,
+                }
+            ]);
+            table.add_row(row![catalog.neighbour_lists, local_search.neighbours]);
+        }
+        table.add_row(row![
+            catalog.candidate_lists,
+            match params.candidates {
+                Some(k) => k.to_string(),
+                None => catalog.every_city.to_owned(),
+            }
+        ]);
+        table.add_row(row![
+            catalog.parallel_construction,
+            if params.parallel {
+                catalog.yes
+            } else {
+                catalog.no
+            }
+        ]);
+        table.add_row(row![
+            catalog.dead_ends,
+            match params.dead_end {
+                DeadEnd::Discard => catalog.discard,
+                DeadEnd::Backtrack => catalog.backtrack,
+                DeadEnd::Repair => catalog.repair,
+            }
+        ]);
+        table.add_row(row![catalog.seed, seed]);
+        table.set_format(*FORMAT_BOX_CHARS);
+
+        let mut out = create_output(path)?;
+        writeln!(out, "{}", catalog.parameters)?;
+        writeln!(out, "{}\n", table)?;
+        text = Some(
+            TextTrace::new(out)
+                .with_language(language)
+                .with_labels(labels.clone()),
+        );
+    }
+
+    let mut json = None;
+    if let Some(path) = json_output {
+        let mut trace = JsonTrace::new(create_output(path)?);
+        trace.event(
+            "parameters",
+            json!({
+                "instance": params.instance,
+                "ants": params.ants,
+                "termination": termination.to_string(),
+                "start": params.start,
+                "alpha": params.alpha,
+                "beta": params.beta,
+                "rho": params.rho,
+                "q": params.q,
+                "initial_pheromone": ant_system.initial_pheromone(),
+                "asymmetric": instance.asymmetric,
+                "mode": match params.mode {
+                    Mode::OpenPath => "path",
+                    Mode::ClosedTour => "tour",
+                },
+                "variant": params.variant.name(),
+                "p_best": params.p_best,
+                "best_so_far_every": params.best_so_far_every,
+                "reinit_after": params.reinit_after,
+                "q0": params.q0,
+                "xi": params.xi,
+                "elitist_weight": params
+                    .elitist_weight
+                    .unwrap_or(instance.dimension as f64),
+                "rank_weight": params.rank_weight,
+                "local_search": params
+                    .local_search
+                    .iter()
+                    .map(|&operator| operator_name(operator))
+                    .collect::<Vec<_>>(),
+                "local_search_on": match params.local_search_on {
+                    Target::EveryAnt => "all",
+                    Target::IterationBest => "best",
+                },
+                "neighbours": params.neighbours,
+                "candidates": params.candidates,
+                "parallel": params.parallel,
+                "dead_end": match params.dead_end {
+                    DeadEnd::Discard => "discard",
+                    DeadEnd::Backtrack => "backtrack",
+                    DeadEnd::Repair => "repair",
+                },
+                "seed": seed,
+                "labels": (0..instance.dimension)
+                    .map(|city| labels.label(city))
+                    .collect::<Vec<_>>(),
+            }),
         )?;
+        json = Some(trace);
+    }
 
-        let solutions_w_costs = ant_system.run(&mut out)?;
-        let min = solutions_w_costs
-            .into_iter()
-            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-            .unwrap();
+    let mut observer = (text, json);
+    let fired = run_with_progress(&mut ant_system, &termination, &mut observer)?;
+    let best = ant_system.best();
+    let failed_ants = ant_system.history().failed_ants();
+    let ants = ant_system.size * ant_system.iteration();
 
+    if let Some(text) = &mut observer.0 {
+        let catalog = text.catalog();
         writeln!(
-            out,
-            "Best path: {} with cost {}\n",
-            min.0.to_display_path()?,
-            min.1
+            text.get_mut(),
+            "\n{}: {}",
+            catalog.stopped_by,
+            fired.localized(catalog)
         )?;
+        writeln!(
+            text.get_mut(),
+            "{}: {} {} {}",
+            catalog.failed_ants,
+            failed_ants,
+            catalog.of,
+            ants
+        )?;
+        if let Some(best) = best {
+            let path = text.labels().path(&best.path);
+            writeln!(
+                text.get_mut(),
+                "{}: {} {} {} ({} {})",
+                catalog.best_global_path,
+                path,
+                catalog.with_cost,
+                best.cost,
+                catalog.found_in_iteration,
+                best.iteration
+            )?;
+        }
+    }
+
+    if let Some(json) = &mut observer.1 {
+        json.event(
+            "finished",
+            json!({
+                "stopped_by": fired.to_string(),
+                "failed_ants": failed_ants,
+                "best": best.map(|best| json!({
+                    "path": best.path,
+                    "cost": best.cost,
+                    "iteration": best.iteration,
+                })),
+            }),
+        )?;
+    }
+
+    write_history(&ant_system, history)?;
+    if best.is_none() {
+        bail!("No ant completed a solution");
+    }
+
+    Ok(())
+}
+
+fn bench(params: &Params, runs: usize, output: Option<&Path>) -> Result<(), Error> {
+    let seed = params.seed();
+    let instance = params.instance()?;
+    let termination = params.termination()?;
+    let mut costs = Vec::new();
+    let mut times = Vec::new();
+    let mut iterations = Vec::new();
+    let mut failed_ants = Vec::new();
 
-        best = Some(min);
+    for run in (0..runs).progress() {
+        let start = Instant::now();
+        let mut ant_system = params.ant_system(&instance, seed.wrapping_add(run as u64))?;
+        ant_system.run_until(&termination, &mut NoopObserver)?;
+
+        if let Some(best) = ant_system.best() {
+            costs.push(best.cost);
+        }
+        times.push(start.elapsed().as_secs_f64());
+        iterations.push(ant_system.iteration() as f64);
+        failed_ants.push(ant_system.history().failed_ants() as f64);
+    }
+
+    if costs.is_empty() {
+        bail!("No ant completed a solution in any run");
     }
 
-    let best = best.unwrap();
-    writeln!(
-        out,
-        "\nBest global path: {} with cost {}",
-        best.0.to_display_path()?,
-        best.1
-    )?;
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let min = |values: &[f64]| values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = |values: &[f64]| values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    let mut table = table! {
+        ["", "Min", "Mean", "Max"],
+        ["Cost", min(&costs), mean(&costs), max(&costs)],
+        ["Iterations", min(&iterations), mean(&iterations), max(&iterations)],
+        ["Failed ants", min(&failed_ants), mean(&failed_ants), max(&failed_ants)],
+        [
+            "Time (s)",
+            format!("{:.3}", min(&times)),
+            format!("{:.3}", mean(&times)),
+            format!("{:.3}", max(&times))
+        ]
+    };
+    table.set_format(*FORMAT_BOX_CHARS);
+
+    println!("Seed: {}", seed);
+    println!("{}", table);
+    if let Some(path) = output {
+        writeln!(create_output(path)?, "{}", table)?;
+    }
 
     Ok(())
 }
+
+fn main() -> Result<(), Error> {
+    match Command::from_args() {
+        Command::Solve {
+            params,
+            output,
+            history,
+        } => solve(&params, output.as_deref(), history.as_deref()),
+        Command::Trace {
+            params,
+            output,
+            format,
+            json_output,
+            lang,
+            history,
+        } => {
+            let text = match format {
+                Format::Text | Format::Both => Some(output.as_path()),
+                Format::Json => None,
+            };
+            let json = match format {
+                Format::Json | Format::Both => Some(json_output.as_path()),
+                Format::Text => None,
+            };
+            trace(&params, lang, text, json, history.as_deref())
+        }
+        Command::Bench {
+            params,
+            runs,
+            output,
+        } => bench(&params, runs, output.as_deref()),
+    }
+}
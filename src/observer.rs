@@ -0,0 +1,728 @@
+use crate::locale::{Catalog, Language};
+use crate::system::{AntSystem, DeadEnd, Solution};
+use crate::utils::{pretty_matrix, CityLabels};
+use anyhow::Error;
+use serde_json::{json, Map, Value};
+use std::io::Write;
+
+/// A city an ant may move to, with the terms of its selection probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    pub city: usize,
+    /// Pheromone on the edge raised to 𝛼.
+    pub pheromone: f64,
+    /// Visibility of the edge raised to 𝛽.
+    pub visibility: f64,
+    /// Product of both terms, the unnormalized probability.
+    pub weight: f64,
+    pub probability: f64,
+}
+
+/// How an ant picked the next city, with the random number that decided it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Choice {
+    /// Roulette wheel selection over the candidate probabilities.
+    Roulette(f64),
+    /// The candidate with the largest weight, taken because the number was
+    /// below `q0` in the Ant Colony System.
+    Greedy(f64),
+    /// The remaining city with the largest weight, taken because every city
+    /// in the candidate list was already visited.
+    Fallback,
+}
+
+/// Receives the events emitted by [`AntSystem::run`].
+///
+/// Every method does nothing by default, so implementors only override the
+/// events they care about. Ants are numbered from 0.
+pub trait Observer {
+    fn iteration_started(&mut self, _iteration: usize, _system: &AntSystem) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn ant_started(&mut self, _ant: usize, _city: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn candidates(
+        &mut self,
+        _ant: usize,
+        _from: usize,
+        _candidates: &[Candidate],
+        _sum: f64,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn city_chosen(&mut self, _ant: usize, _city: usize, _choice: Choice) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `ant` can't go on from the last city of `path` and gets out with
+    /// `strategy`. It's called again if backtracking leads to another dead
+    /// end.
+    fn dead_end(&mut self, _ant: usize, _path: &[usize], _strategy: DeadEnd) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `ant` couldn't get out of a dead end, it has no solution this
+    /// iteration.
+    fn ant_failed(&mut self, _ant: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn tour_finished(&mut self, _ant: usize, _path: &[usize], _cost: f64) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// The solution of `ant` after the local search improved it.
+    fn tour_improved(&mut self, _ant: usize, _path: &[usize], _cost: f64) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Whether [`pheromone_updated`](Observer::pheromone_updated) is called
+    /// for every cell of the matrix after the pheromone update of an
+    /// iteration. Reporting them costs O(n² · m), so it's skipped when this
+    /// returns `false`.
+    fn wants_pheromone_updates(&self) -> bool {
+        true
+    }
+
+    /// `deposits` holds the pheromone added by every depositing solution, zero
+    /// if it doesn't use the edge. Every ant deposits in the classic Ant
+    /// System, other variants use fewer solutions. The local update of the
+    /// Ant Colony System reports the τ0 share as its only deposit.
+    fn pheromone_updated(
+        &mut self,
+        _from: usize,
+        _to: usize,
+        _evaporated: f64,
+        _deposits: &[f64],
+        _value: f64,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `solutions` leaves out the ants that failed, and `best` is `None`
+    /// until some ant completes a solution.
+    fn iteration_finished(
+        &mut self,
+        _iteration: usize,
+        _solutions: &[(Vec<usize>, f64)],
+        _best: Option<&Solution>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Ignores every event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {
+    fn wants_pheromone_updates(&self) -> bool {
+        false
+    }
+}
+
+/// Forwards every event to the observer if there's one, so an optional
+/// observer can be combined with others.
+impl<O: Observer> Observer for Option<O> {
+    fn iteration_started(&mut self, iteration: usize, system: &AntSystem) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.iteration_started(iteration, system),
+            None => Ok(()),
+        }
+    }
+
+    fn ant_started(&mut self, ant: usize, city: usize) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.ant_started(ant, city),
+            None => Ok(()),
+        }
+    }
+
+    fn candidates(
+        &mut self,
+        ant: usize,
+        from: usize,
+        candidates: &[Candidate],
+        sum: f64,
+    ) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.candidates(ant, from, candidates, sum),
+            None => Ok(()),
+        }
+    }
+
+    fn city_chosen(&mut self, ant: usize, city: usize, choice: Choice) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.city_chosen(ant, city, choice),
+            None => Ok(()),
+        }
+    }
+
+    fn dead_end(&mut self, ant: usize, path: &[usize], strategy: DeadEnd) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.dead_end(ant, path, strategy),
+            None => Ok(()),
+        }
+    }
+
+    fn ant_failed(&mut self, ant: usize) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.ant_failed(ant),
+            None => Ok(()),
+        }
+    }
+
+    fn tour_finished(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.tour_finished(ant, path, cost),
+            None => Ok(()),
+        }
+    }
+
+    fn tour_improved(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.tour_improved(ant, path, cost),
+            None => Ok(()),
+        }
+    }
+
+    fn wants_pheromone_updates(&self) -> bool {
+        match self {
+            Some(observer) => observer.wants_pheromone_updates(),
+            None => false,
+        }
+    }
+
+    fn pheromone_updated(
+        &mut self,
+        from: usize,
+        to: usize,
+        evaporated: f64,
+        deposits: &[f64],
+        value: f64,
+    ) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.pheromone_updated(from, to, evaporated, deposits, value),
+            None => Ok(()),
+        }
+    }
+
+    fn iteration_finished(
+        &mut self,
+        iteration: usize,
+        solutions: &[(Vec<usize>, f64)],
+        best: Option<&Solution>,
+    ) -> Result<(), Error> {
+        match self {
+            Some(observer) => observer.iteration_finished(iteration, solutions, best),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Forwards every event to both observers, first to `A` and then to `B`.
+impl<A: Observer, B: Observer> Observer for (A, B) {
+    fn iteration_started(&mut self, iteration: usize, system: &AntSystem) -> Result<(), Error> {
+        self.0.iteration_started(iteration, system)?;
+        self.1.iteration_started(iteration, system)
+    }
+
+    fn ant_started(&mut self, ant: usize, city: usize) -> Result<(), Error> {
+        self.0.ant_started(ant, city)?;
+        self.1.ant_started(ant, city)
+    }
+
+    fn candidates(
+        &mut self,
+        ant: usize,
+        from: usize,
+        candidates: &[Candidate],
+        sum: f64,
+    ) -> Result<(), Error> {
+        self.0.candidates(ant, from, candidates, sum)?;
+        self.1.candidates(ant, from, candidates, sum)
+    }
+
+    fn city_chosen(&mut self, ant: usize, city: usize, choice: Choice) -> Result<(), Error> {
+        self.0.city_chosen(ant, city, choice)?;
+        self.1.city_chosen(ant, city, choice)
+    }
+
+    fn dead_end(&mut self, ant: usize, path: &[usize], strategy: DeadEnd) -> Result<(), Error> {
+        self.0.dead_end(ant, path, strategy)?;
+        self.1.dead_end(ant, path, strategy)
+    }
+
+    fn ant_failed(&mut self, ant: usize) -> Result<(), Error> {
+        self.0.ant_failed(ant)?;
+        self.1.ant_failed(ant)
+    }
+
+    fn tour_finished(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        self.0.tour_finished(ant, path, cost)?;
+        self.1.tour_finished(ant, path, cost)
+    }
+
+    fn tour_improved(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        self.0.tour_improved(ant, path, cost)?;
+        self.1.tour_improved(ant, path, cost)
+    }
+
+    fn wants_pheromone_updates(&self) -> bool {
+        self.0.wants_pheromone_updates() || self.1.wants_pheromone_updates()
+    }
+
+    fn pheromone_updated(
+        &mut self,
+        from: usize,
+        to: usize,
+        evaporated: f64,
+        deposits: &[f64],
+        value: f64,
+    ) -> Result<(), Error> {
+        if self.0.wants_pheromone_updates() {
+            self.0
+                .pheromone_updated(from, to, evaporated, deposits, value)?;
+        }
+
+        if self.1.wants_pheromone_updates() {
+            self.1
+                .pheromone_updated(from, to, evaporated, deposits, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn iteration_finished(
+        &mut self,
+        iteration: usize,
+        solutions: &[(Vec<usize>, f64)],
+        best: Option<&Solution>,
+    ) -> Result<(), Error> {
+        self.0.iteration_finished(iteration, solutions, best)?;
+        self.1.iteration_finished(iteration, solutions, best)
+    }
+}
+
+/// Writes a detailed, human readable trace of every event.
+#[derive(Debug)]
+pub struct TextTrace<W: Write> {
+    out: W,
+    catalog: &'static Catalog,
+    labels: CityLabels,
+}
+
+impl<W: Write> TextTrace<W> {
+    /// Writes the trace in Spanish, naming cities with letters.
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            catalog: Language::Spanish.catalog(),
+            labels: CityLabels::Letters,
+        }
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.catalog = language.catalog();
+        self
+    }
+
+    pub fn with_labels(mut self, labels: CityLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn catalog(&self) -> &'static Catalog {
+        self.catalog
+    }
+
+    pub fn labels(&self) -> &CityLabels {
+        &self.labels
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Observer for TextTrace<W> {
+    fn iteration_started(&mut self, iteration: usize, system: &AntSystem) -> Result<(), Error> {
+        writeln!(self.out, "------------------------------------")?;
+        writeln!(self.out, "{} {}\n", self.catalog.iteration, iteration)?;
+
+        writeln!(
+            self.out,
+            "{}:\n{}",
+            self.catalog.visibility_matrix,
+            pretty_matrix(&system.visibility, 6, &self.labels)
+        )?;
+
+        writeln!(
+            self.out,
+            "{}:\n{}",
+            self.catalog.pheromone_matrix,
+            pretty_matrix(&system.pheromones, 6, &self.labels)
+        )?;
+
+        Ok(())
+    }
+
+    fn ant_started(&mut self, ant: usize, city: usize) -> Result<(), Error> {
+        writeln!(self.out, "{} {}", self.catalog.ant, ant + 1)?;
+        writeln!(
+            self.out,
+            "{}: {}",
+            self.catalog.start_city,
+            self.labels.label(city)
+        )?;
+        Ok(())
+    }
+
+    fn candidates(
+        &mut self,
+        _ant: usize,
+        from: usize,
+        candidates: &[Candidate],
+        sum: f64,
+    ) -> Result<(), Error> {
+        for candidate in candidates {
+            writeln!(
+                self.out,
+                "{} -> {}: 𝜏^𝛼 = {}, 𝜂^𝛽 = {}, (𝜏^𝛼) * (𝜂^𝛽) = {}",
+                self.labels.label(from),
+                self.labels.label(candidate.city),
+                candidate.pheromone,
+                candidate.visibility,
+                candidate.weight
+            )?;
+        }
+
+        writeln!(self.out, "{}: {}", self.catalog.sum, sum)?;
+
+        for candidate in candidates {
+            writeln!(
+                self.out,
+                "{} -> {}: prob = {}",
+                self.labels.label(from),
+                self.labels.label(candidate.city),
+                candidate.probability
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn city_chosen(&mut self, _ant: usize, city: usize, choice: Choice) -> Result<(), Error> {
+        let catalog = self.catalog;
+        match choice {
+            Choice::Roulette(draw) => writeln!(self.out, "{}: {}", catalog.random_number, draw)?,
+            Choice::Greedy(draw) => writeln!(
+                self.out,
+                "{}: {} {}",
+                catalog.random_number, draw, catalog.greedy_choice
+            )?,
+            Choice::Fallback => writeln!(self.out, "{}", catalog.fallback_choice)?,
+        }
+
+        writeln!(
+            self.out,
+            "{}: {}\n",
+            catalog.next_city,
+            self.labels.label(city)
+        )?;
+        Ok(())
+    }
+
+    fn dead_end(&mut self, ant: usize, path: &[usize], strategy: DeadEnd) -> Result<(), Error> {
+        writeln!(
+            self.out,
+            "{} {} {}: {}, {}\n",
+            self.catalog.ant,
+            ant + 1,
+            self.catalog.stuck,
+            self.labels.path(path),
+            match strategy {
+                DeadEnd::Discard => self.catalog.discarding,
+                DeadEnd::Backtrack => self.catalog.backtracking,
+                DeadEnd::Repair => self.catalog.repairing,
+            }
+        )?;
+
+        Ok(())
+    }
+
+    fn ant_failed(&mut self, ant: usize) -> Result<(), Error> {
+        writeln!(
+            self.out,
+            "{} {} {}\n---\n",
+            self.catalog.ant,
+            ant + 1,
+            self.catalog.ant_failed
+        )?;
+
+        Ok(())
+    }
+
+    fn tour_finished(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        writeln!(
+            self.out,
+            "{} {}: {} ({}: {})\n---\n",
+            self.catalog.ant_path,
+            ant + 1,
+            self.labels.path(path),
+            self.catalog.cost,
+            cost
+        )?;
+
+        Ok(())
+    }
+
+    fn tour_improved(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        writeln!(
+            self.out,
+            "{} {}: {} ({}: {})",
+            self.catalog.local_search_ant,
+            ant + 1,
+            self.labels.path(path),
+            self.catalog.cost,
+            cost
+        )?;
+
+        Ok(())
+    }
+
+    fn pheromone_updated(
+        &mut self,
+        from: usize,
+        to: usize,
+        evaporated: f64,
+        deposits: &[f64],
+        value: f64,
+    ) -> Result<(), Error> {
+        write!(
+            self.out,
+            "{} -> {}: {} = {} ",
+            self.labels.label(from),
+            self.labels.label(to),
+            self.catalog.pheromone,
+            evaporated
+        )?;
+
+        for &deposit in deposits {
+            if deposit == 0.0 {
+                write!(self.out, "+ 0.0 ")?;
+            } else {
+                write!(self.out, "+ {} ", deposit)?;
+            }
+        }
+
+        writeln!(self.out, "= {}", value)?;
+        Ok(())
+    }
+
+    fn iteration_finished(
+        &mut self,
+        _iteration: usize,
+        solutions: &[(Vec<usize>, f64)],
+        _best: Option<&Solution>,
+    ) -> Result<(), Error> {
+        let iteration_best = solutions
+            .iter()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+        if let Some((path, cost)) = iteration_best {
+            writeln!(
+                self.out,
+                "{}: {} {} {}\n",
+                self.catalog.best_path,
+                self.labels.path(path),
+                self.catalog.with_cost,
+                cost
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes every event as a JSON object on its own line (JSON Lines).
+///
+/// Each object has an `event` field with the name of the observer method,
+/// the remaining fields are its arguments. Cities and ants are numbered from
+/// 0 and non finite numbers, such as the visibility of the diagonal, are
+/// written as `null`.
+#[derive(Debug)]
+pub struct JsonTrace<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonTrace<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    /// Writes the fields of the `fields` object as one more line of the trace,
+    /// tagged with `event`. This is how callers add events the [`Observer`]
+    /// trait doesn't emit, such as the run parameters.
+    pub fn event(&mut self, event: &str, fields: Value) -> Result<(), Error> {
+        let mut line = Map::new();
+        line.insert("event".to_owned(), Value::from(event));
+        if let Value::Object(fields) = fields {
+            line.extend(fields);
+        }
+
+        serde_json::to_writer(&mut self.out, &line)?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Observer for JsonTrace<W> {
+    fn iteration_started(&mut self, iteration: usize, system: &AntSystem) -> Result<(), Error> {
+        let rows = |matrix: &ndarray::Array2<f64>| -> Vec<Vec<f64>> {
+            matrix.outer_iter().map(|row| row.to_vec()).collect()
+        };
+
+        self.event(
+            "iteration_started",
+            json!({
+                "iteration": iteration,
+                "visibility": rows(&system.visibility),
+                "pheromones": rows(&system.pheromones),
+            }),
+        )
+    }
+
+    fn ant_started(&mut self, ant: usize, city: usize) -> Result<(), Error> {
+        self.event("ant_started", json!({ "ant": ant, "city": city }))
+    }
+
+    fn candidates(
+        &mut self,
+        ant: usize,
+        from: usize,
+        candidates: &[Candidate],
+        sum: f64,
+    ) -> Result<(), Error> {
+        let candidates: Vec<_> = candidates
+            .iter()
+            .map(|candidate| {
+                json!({
+                    "city": candidate.city,
+                    "pheromone": candidate.pheromone,
+                    "visibility": candidate.visibility,
+                    "weight": candidate.weight,
+                    "probability": candidate.probability,
+                })
+            })
+            .collect();
+
+        self.event(
+            "candidates",
+            json!({ "ant": ant, "from": from, "candidates": candidates, "sum": sum }),
+        )
+    }
+
+    fn city_chosen(&mut self, ant: usize, city: usize, choice: Choice) -> Result<(), Error> {
+        let (rule, draw) = match choice {
+            Choice::Roulette(draw) => ("roulette", Some(draw)),
+            Choice::Greedy(draw) => ("greedy", Some(draw)),
+            Choice::Fallback => ("fallback", None),
+        };
+
+        self.event(
+            "city_chosen",
+            json!({ "ant": ant, "city": city, "choice": rule, "draw": draw }),
+        )
+    }
+
+    fn dead_end(&mut self, ant: usize, path: &[usize], strategy: DeadEnd) -> Result<(), Error> {
+        let strategy = match strategy {
+            DeadEnd::Discard => "discard",
+            DeadEnd::Backtrack => "backtrack",
+            DeadEnd::Repair => "repair",
+        };
+
+        self.event(
+            "dead_end",
+            json!({ "ant": ant, "path": path, "strategy": strategy }),
+        )
+    }
+
+    fn ant_failed(&mut self, ant: usize) -> Result<(), Error> {
+        self.event("ant_failed", json!({ "ant": ant }))
+    }
+
+    fn tour_finished(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        self.event(
+            "tour_finished",
+            json!({ "ant": ant, "path": path, "cost": cost }),
+        )
+    }
+
+    fn tour_improved(&mut self, ant: usize, path: &[usize], cost: f64) -> Result<(), Error> {
+        self.event(
+            "tour_improved",
+            json!({ "ant": ant, "path": path, "cost": cost }),
+        )
+    }
+
+    fn pheromone_updated(
+        &mut self,
+        from: usize,
+        to: usize,
+        evaporated: f64,
+        deposits: &[f64],
+        value: f64,
+    ) -> Result<(), Error> {
+        self.event(
+            "pheromone_updated",
+            json!({
+                "from": from,
+                "to": to,
+                "evaporated": evaporated,
+                "deposits": deposits,
+                "value": value,
+            }),
+        )
+    }
+
+    fn iteration_finished(
+        &mut self,
+        iteration: usize,
+        solutions: &[(Vec<usize>, f64)],
+        best: Option<&Solution>,
+    ) -> Result<(), Error> {
+        let solutions: Vec<_> = solutions
+            .iter()
+            .map(|(path, cost)| json!({ "path": path, "cost": cost }))
+            .collect();
+
+        self.event(
+            "iteration_finished",
+            json!({
+                "iteration": iteration,
+                "solutions": solutions,
+                "best": best.map(|best| {
+                    json!({ "path": best.path, "cost": best.cost, "iteration": best.iteration })
+                }),
+            }),
+        )
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::Error;
+use std::io::Write;
+use std::time::Duration;
+
+/// λ used for the branching factor recorded in every [`IterationStats`].
+pub const BRANCHING_LAMBDA: f64 = 0.05;
+
+/// Summary of one iteration, taken after the pheromone update.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationStats {
+    pub iteration: usize,
+    /// Ants that failed at a dead end, the cost statistics leave them out and
+    /// are NaN if every ant failed.
+    pub failed_ants: usize,
+    /// Cost of the best ant of the iteration.
+    pub best: f64,
+    pub mean: f64,
+    /// Cost of the worst ant of the iteration.
+    pub worst: f64,
+    /// Population standard deviation of the ant costs.
+    pub std_dev: f64,
+    /// NaN until some ant completes a solution.
+    pub best_so_far: f64,
+    /// Limits and mean of the trails, the diagonal and missing edges excluded.
+    pub pheromone_min: f64,
+    pub pheromone_max: f64,
+    pub pheromone_mean: f64,
+    /// Average λ-branching factor with λ = [`BRANCHING_LAMBDA`].
+    pub branching_factor: f64,
+    /// Time since the first iteration started.
+    pub elapsed: Duration,
+}
+
+/// Statistics of every iteration run so far, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    pub iterations: Vec<IterationStats>,
+}
+
+impl History {
+    /// Ants that failed at a dead end across every iteration.
+    pub fn failed_ants(&self) -> usize {
+        self.iterations.iter().map(|stats| stats.failed_ants).sum()
+    }
+
+    /// Writes one CSV row per iteration, after a header with the field names.
+    /// `elapsed` is written in seconds.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> Result<(), Error> {
+        writeln!(
+            out,
+            "iteration,failed_ants,best,mean,worst,std_dev,best_so_far,\
+             pheromone_min,pheromone_max,pheromone_mean,branching_factor,elapsed"
+        )?;
+
+        for stats in &self.iterations {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                stats.iteration,
+                stats.failed_ants,
+                stats.best,
+                stats.mean,
+                stats.worst,
+                stats.std_dev,
+                stats.best_so_far,
+                stats.pheromone_min,
+                stats.pheromone_max,
+                stats.pheromone_mean,
+                stats.branching_factor,
+                stats.elapsed.as_secs_f64()
+            )?;
+        }
+
+        Ok(())
+    }
+}
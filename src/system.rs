@@ -1,27 +1,168 @@
-use crate::utils::{ToCharIndex, ToDisplayPath};
-use anyhow::Error;
-use ndarray::{Array2, Ix2, ShapeBuilder};
-use rand::{thread_rng, Rng};
-use std::io::Write;
+use crate::error::ValidationError;
+use crate::local_search::{nearest_neighbours, LocalSearch, Target};
+use crate::observer::{Candidate, Choice, NoopObserver, Observer};
+use crate::stats::{History, IterationStats, BRANCHING_LAMBDA};
+use crate::termination::Termination;
+use crate::variant::{MaxMinProps, Variant};
+use anyhow::{anyhow, Error};
+use ndarray::{Array2, Zip};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::str::FromStr;
+use std::time::Instant;
 
-fn init_pheromone_matrix<S>(shape: S, value: f64) -> Array2<f64>
-where
-    S: ShapeBuilder<Dim = Ix2>,
-{
-    Array2::from_shape_fn(shape, |(i, j)| if i == j { 0.0 } else { value })
+/// Trails start at `value` on every edge but loops and missing edges, no ant
+/// ever walks those.
+fn init_pheromone_matrix(distances: &Array2<f64>, value: f64) -> Array2<f64> {
+    Array2::from_shape_fn(distances.raw_dim(), |(i, j)| {
+        if i == j || !distances[[i, j]].is_finite() {
+            0.0
+        } else {
+            value
+        }
+    })
 }
 
 fn compute_visiblity_matrix(distances: &Array2<f64>) -> Array2<f64> {
     distances.mapv(|v| 1.0 / v)
 }
 
-fn compute_cost(solution: &[usize], distances: &Array2<f64>) -> f64 {
-    solution
-        .windows(2)
-        .fold(0.0, |acc, edge| acc + distances[[edge[0], edge[1]]])
+fn nearest_neighbour_cost(distances: &Array2<f64>, initial: usize, mode: Mode) -> f64 {
+    let no_cities = distances.shape()[0];
+
+    let mut visited = vec![false; no_cities];
+    let mut solution = vec![initial];
+    visited[initial] = true;
+
+    while solution.len() != no_cities {
+        let curr = *solution.last().expect("No cities visited?");
+        let next = (0..no_cities)
+            .filter(|&city| !visited[city] && distances[[curr, city]].is_finite())
+            .min_by(|&a, &b| {
+                distances[[curr, a]]
+                    .partial_cmp(&distances[[curr, b]])
+                    .unwrap()
+            });
+
+        match next {
+            Some(next) => {
+                visited[next] = true;
+                solution.push(next);
+            }
+            None => break,
+        }
+    }
+
+    let cost = compute_cost(&solution, distances, mode);
+    if solution.len() == no_cities && cost.is_finite() {
+        return cost;
+    }
+
+    // The heuristic got stuck on a sparse graph, estimate the cost from the
+    // mean length of the existing edges instead.
+    let (sum, count) = distances
+        .indexed_iter()
+        .filter(|&((r, c), distance)| r != c && distance.is_finite())
+        .fold((0.0, 0), |(sum, count), (_, distance)| {
+            (sum + distance, count + 1)
+        });
+
+    no_cities as f64 * sum / count as f64
+}
+
+fn solution_edges(solution: &[usize], mode: Mode) -> Vec<(usize, usize)> {
+    let mut edges: Vec<_> = solution.windows(2).map(|edge| (edge[0], edge[1])).collect();
+
+    if mode == Mode::ClosedTour && solution.len() > 1 {
+        edges.push((solution[solution.len() - 1], solution[0]));
+    }
+
+    edges
 }
 
-#[derive(Debug, Clone, Default)]
+fn compute_cost(solution: &[usize], distances: &Array2<f64>, mode: Mode) -> f64 {
+    solution_edges(solution, mode)
+        .into_iter()
+        .fold(0.0, |acc, (from, to)| acc + distances[[from, to]])
+}
+
+fn best_candidate(candidates: &[Candidate]) -> &Candidate {
+    candidates
+        .iter()
+        .max_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap())
+        .expect("At least one candidate")
+}
+
+/// Whether ants stop at the last unvisited city or return to the initial one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    OpenPath,
+    ClosedTour,
+}
+
+impl FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "path" => Ok(Mode::OpenPath),
+            "tour" => Ok(Mode::ClosedTour),
+            other => Err(anyhow!("Unknown mode {}, expected path or tour", other)),
+        }
+    }
+}
+
+/// What an ant does when no unvisited city can be reached from its current
+/// one, or in [`Mode::ClosedTour`] when the last city has no edge back to
+/// the initial one. Only sparse graphs have dead ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeadEnd {
+    /// The ant fails and its solution is dropped.
+    Discard,
+    /// The ant goes back to the previous city and chooses among the cities
+    /// it didn't try from there yet. It fails after backtracking as many
+    /// times as there are cities.
+    Backtrack,
+    /// The remaining cities are inserted where they increase the cost the
+    /// least, among the positions where both new edges exist. The ant fails
+    /// if one of them doesn't fit anywhere.
+    Repair,
+}
+
+impl FromStr for DeadEnd {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "discard" => Ok(DeadEnd::Discard),
+            "backtrack" => Ok(DeadEnd::Backtrack),
+            "repair" => Ok(DeadEnd::Repair),
+            other => Err(anyhow!(
+                "Unknown dead end strategy {}, expected discard, backtrack or repair",
+                other
+            )),
+        }
+    }
+}
+
+/// A path built by an ant together with its cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// Visited cities in order, starting at the initial city. In
+    /// [`Mode::ClosedTour`] the return to the initial city is implied.
+    pub path: Vec<usize>,
+    /// Sum of the distances of every edge in the path.
+    pub cost: f64,
+    /// Iteration (starting at 1) in which the path was found.
+    pub iteration: usize,
+}
+
+/// An Ant System colony over a fixed distance matrix.
+///
+/// Every call to [`AntSystem::run`] performs one iteration: each ant builds a
+/// solution and then the pheromone trails are updated.
+#[derive(Debug, Clone)]
 pub struct AntSystem {
     pub alpha: f64,
     pub beta: f64,
@@ -30,198 +171,1046 @@ pub struct AntSystem {
 
     pub size: usize,
     pub initial: usize,
+    pub mode: Mode,
+    pub asymmetric: bool,
 
     pub distances: Array2<f64>,
     pub visibility: Array2<f64>,
     pub pheromones: Array2<f64>,
+    pub variant: Variant,
+    pub local_search: Option<LocalSearch>,
+    pub parallel: bool,
+    pub dead_end: DeadEnd,
+
+    rng: StdRng,
+    iteration: usize,
+    best: Option<Solution>,
+    last_reset: usize,
+    initial_pheromone: f64,
+    neighbours: Vec<Vec<usize>>,
+    candidate_lists: Vec<Vec<usize>>,
+
+    // Terms of the selection probability refreshed before every iteration:
+    // 𝜏^𝛼, 𝜂^𝛽 (only when 𝛽 changes) and their product.
+    trail: Array2<f64>,
+    heuristic: Array2<f64>,
+    heuristic_beta: f64,
+    choice_info: Array2<f64>,
+
+    started: Option<Instant>,
+    history: History,
 }
 
+/// Parameters used to build an [`AntSystem`].
 pub struct AntProps {
+    /// Influence of the pheromone trail (𝛼).
     pub alpha: f64,
+    /// Influence of the visibility, the inverse of the distance (𝛽).
     pub beta: f64,
+    /// Fraction of pheromone kept on every edge after an iteration (𝜌).
     pub rho: f64,
+    /// Pheromone deposited by an ant, divided by the cost of its solution.
     pub q: f64,
+    /// Pheromone on every edge before the first iteration.
     pub initial_pheromone: f64,
+    /// Square matrix with the distance between every pair of cities. An
+    /// infinite distance marks a missing edge, ants never travel it.
     pub distances: Array2<f64>,
+    pub mode: Mode,
+    /// Whether the distance between two cities depends on the direction of
+    /// travel (ATSP). Trails are then directional too, so ants only deposit
+    /// on the direction they traveled. Otherwise `distances` must be
+    /// symmetric. The local search operators assume symmetric distances and
+    /// can't be used with this mode.
+    pub asymmetric: bool,
+    /// Pheromone update rule. [`Variant::MaxMin`] and [`Variant::ColonySystem`]
+    /// derive their initial trails and ignore `initial_pheromone`.
+    pub variant: Variant,
+    /// Improvement applied to the solutions before the pheromone update.
+    pub local_search: Option<LocalSearch>,
+    /// Length of the candidate list of every city. Ants choose among the
+    /// closest unvisited cities and take the best remaining one only once all
+    /// of them were visited. Every unvisited city is a candidate if `None`.
+    pub candidates: Option<usize>,
+    /// Builds the solutions of all ants in parallel. Every ant draws from its
+    /// own generator seeded from the main one, so results don't depend on this
+    /// flag, except in the Ant Colony System where ants no longer see the
+    /// local updates of the ants built before them. Observers only receive
+    /// the start and the finished tour of every ant.
+    pub parallel: bool,
+    /// How ants get out of a dead end on a sparse graph.
+    pub dead_end: DeadEnd,
+    /// Seed of the random number generator used to choose cities.
+    pub seed: u64,
+}
+
+impl AntProps {
+    /// Parameters for `distances` with the defaults of the command line:
+    /// 𝛼 = 𝛽 = Q = 1, 𝜌 = 0.99, an initial pheromone of 0.1, a symmetric
+    /// problem solved as an open path by the original Ant System, no local
+    /// search nor candidate lists, sequential construction, failed ants
+    /// discarded at dead ends and seed 0. Override the rest with struct update
+    /// syntax:
+    ///
+    /// ```
+    /// use ant_system::{AntProps, Mode};
+    /// use ndarray::arr2;
+    ///
+    /// let props = AntProps {
+    ///     mode: Mode::ClosedTour,
+    ///     ..AntProps::new(arr2(&[[0.0, 2.0], [2.0, 0.0]]))
+    /// };
+    /// ```
+    pub fn new(distances: Array2<f64>) -> Self {
+        AntProps {
+            alpha: 1.0,
+            beta: 1.0,
+            rho: 0.99,
+            q: 1.0,
+            initial_pheromone: 0.1,
+            distances,
+            mode: Mode::OpenPath,
+            asymmetric: false,
+            variant: Variant::AntSystem,
+            local_search: None,
+            candidates: None,
+            parallel: false,
+            dead_end: DeadEnd::Discard,
+            seed: 0,
+        }
+    }
+
+    /// Checks that a colony of `size` ants starting at city `initial` can
+    /// be built from these parameters, reporting the first invalid one.
+    pub fn validate(&self, size: usize, initial: usize) -> Result<(), ValidationError> {
+        let (rows, columns) = self.distances.dim();
+        if rows != columns {
+            return Err(ValidationError::NotSquare { rows, columns });
+        }
+
+        if rows < 2 {
+            return Err(ValidationError::TooFewCities { cities: rows });
+        }
+
+        if initial >= rows {
+            return Err(ValidationError::StartOutOfRange {
+                start: initial,
+                cities: rows,
+            });
+        }
+
+        for ((from, to), &distance) in self.distances.indexed_iter() {
+            if from == to {
+                continue;
+            }
+
+            if distance.is_nan() || distance < 0.0 {
+                return Err(ValidationError::InvalidDistance { from, to, distance });
+            }
+
+            if distance == 0.0 {
+                return Err(ValidationError::ZeroDistance { from, to });
+            }
+        }
+
+        // Only once every cell is valid, so a bad cell is reported as such
+        // rather than as differing from its mirror.
+        if !self.asymmetric {
+            for ((from, to), &distance) in self.distances.indexed_iter() {
+                if distance != self.distances[[to, from]] && from != to {
+                    return Err(ValidationError::Asymmetric { from, to });
+                }
+            }
+        }
+
+        for city in 0..rows {
+            let connected = |edges: ndarray::ArrayView1<f64>| {
+                edges
+                    .indexed_iter()
+                    .any(|(other, distance)| other != city && distance.is_finite())
+            };
+
+            if !connected(self.distances.row(city)) || !connected(self.distances.column(city)) {
+                return Err(ValidationError::Isolated { city });
+            }
+        }
+
+        if self.asymmetric && self.local_search.is_some() {
+            return Err(ValidationError::AsymmetricLocalSearch);
+        }
+
+        if size == 0 {
+            return Err(ValidationError::NoAnts);
+        }
+
+        if self.candidates == Some(0) {
+            return Err(ValidationError::NoCandidates);
+        }
+
+        type Range = (fn(f64) -> bool, &'static str);
+        const NON_NEGATIVE: Range = (|v| v.is_finite() && v >= 0.0, "finite and non negative");
+        const POSITIVE: Range = (|v| v.is_finite() && v > 0.0, "finite and positive");
+        const OPEN_UNIT: Range = (|v| v > 0.0 && v < 1.0, "in (0, 1)");
+        const HALF_OPEN_UNIT: Range = (|v| v > 0.0 && v <= 1.0, "in (0, 1]");
+        const CLOSED_UNIT: Range = (|v| (0.0..=1.0).contains(&v), "in [0, 1]");
+
+        let check = |name, value: f64, (valid, expected): Range| {
+            if valid(value) {
+                Ok(())
+            } else {
+                Err(ValidationError::InvalidParameter {
+                    name,
+                    value,
+                    expected,
+                })
+            }
+        };
+
+        check("alpha", self.alpha, NON_NEGATIVE)?;
+        check("beta", self.beta, NON_NEGATIVE)?;
+        check("q", self.q, POSITIVE)?;
+
+        // τ_max of the Max-Min Ant System divides by 1 - 𝜌, trails must evaporate.
+        let rho = match self.variant {
+            Variant::MaxMin(_) => OPEN_UNIT,
+            _ => HALF_OPEN_UNIT,
+        };
+        check("rho", self.rho, rho)?;
+
+        match &self.variant {
+            Variant::MaxMin(_) | Variant::ColonySystem(_) => {}
+            _ => check("initial_pheromone", self.initial_pheromone, POSITIVE)?,
+        }
+
+        match &self.variant {
+            Variant::AntSystem => Ok(()),
+            Variant::MaxMin(props) => check("p_best", props.p_best, OPEN_UNIT),
+            Variant::ColonySystem(props) => {
+                check("q0", props.q0, CLOSED_UNIT)?;
+                check("xi", props.xi, HALF_OPEN_UNIT)
+            }
+            Variant::Elitist(props) => check("e", props.e, NON_NEGATIVE),
+            Variant::RankBased(props) => check("w", props.w as f64, (|v| v >= 1.0, "at least 1")),
+        }
+    }
 }
 
 impl AntSystem {
-    pub fn new(size: usize, initial: usize, props: AntProps) -> Self {
+    /// Creates a colony of `size` ants, all of them starting at city `initial`.
+    /// Fails if any of the arguments is invalid, see [`AntProps::validate`].
+    pub fn new(size: usize, initial: usize, props: AntProps) -> Result<Self, ValidationError> {
+        props.validate(size, initial)?;
+
         let shape = props.distances.raw_dim();
 
-        let pheromones = init_pheromone_matrix(shape, props.initial_pheromone);
+        let initial_pheromone = match props.variant {
+            Variant::MaxMin(_) => {
+                let cost = nearest_neighbour_cost(&props.distances, initial, props.mode);
+                props.q / ((1.0 - props.rho) * cost)
+            }
+            Variant::ColonySystem(_) => {
+                let cost = nearest_neighbour_cost(&props.distances, initial, props.mode);
+                props.q / (shape[0] as f64 * cost)
+            }
+            Variant::AntSystem | Variant::Elitist(_) | Variant::RankBased(_) => {
+                props.initial_pheromone
+            }
+        };
+
+        let pheromones = init_pheromone_matrix(&props.distances, initial_pheromone);
         let visibility = compute_visiblity_matrix(&props.distances);
+        let candidate_lists = match props.candidates {
+            Some(k) => nearest_neighbours(&props.distances, k),
+            None => Vec::new(),
+        };
+        let heuristic = visibility.mapv(|visibility| visibility.powf(props.beta));
         let distances = props.distances;
 
-        Self {
+        Ok(Self {
             alpha: props.alpha,
             beta: props.beta,
             rho: props.rho,
             q: props.q,
             size,
             initial,
+            mode: props.mode,
+            asymmetric: props.asymmetric,
             distances,
             visibility,
             pheromones,
+            variant: props.variant,
+            local_search: props.local_search,
+            parallel: props.parallel,
+            dead_end: props.dead_end,
+            rng: StdRng::seed_from_u64(props.seed),
+            iteration: 0,
+            best: None,
+            last_reset: 0,
+            initial_pheromone,
+            neighbours: Vec::new(),
+            candidate_lists,
+            trail: Array2::zeros(shape),
+            heuristic,
+            heuristic_beta: props.beta,
+            choice_info: Array2::zeros(shape),
+            started: None,
+            history: History::default(),
+        })
+    }
+
+    /// Best solution found so far across every iteration.
+    pub fn best(&self) -> Option<&Solution> {
+        self.best.as_ref()
+    }
+
+    /// Number of iterations run so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Pheromone every edge started with, derived from a nearest neighbour
+    /// solution by [`Variant::MaxMin`] and [`Variant::ColonySystem`].
+    pub fn initial_pheromone(&self) -> f64 {
+        self.initial_pheromone
+    }
+
+    /// Statistics of every iteration run so far.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Average λ-branching factor of the pheromone matrix: the mean number of
+    /// existing edges leaving a city whose trail is at least
+    /// τ_min + λ · (τ_max - τ_min), with the limits taken over the edges of
+    /// that city. It approaches 2 as the colony converges to a single tour.
+    pub fn branching_factor(&self, lambda: f64) -> f64 {
+        let no_cities = self.pheromones.shape()[0];
+        if no_cities < 2 {
+            return 0.0;
         }
+
+        let branches: usize = self
+            .pheromones
+            .outer_iter()
+            .enumerate()
+            .map(|(city, row)| {
+                let edges = || {
+                    row.iter()
+                        .enumerate()
+                        .filter(|&(to, _)| to != city && self.distances[[city, to]].is_finite())
+                };
+                let min = edges().map(|(_, &t)| t).fold(f64::INFINITY, f64::min);
+                let max = edges().map(|(_, &t)| t).fold(f64::NEG_INFINITY, f64::max);
+                let cutoff = min + lambda * (max - min);
+                edges().filter(|&(_, &t)| t >= cutoff).count()
+            })
+            .sum();
+
+        branches as f64 / no_cities as f64
     }
 
-    pub fn run<W: Write>(&mut self, out: &mut W) -> Result<Vec<(Vec<usize>, f64)>, Error> {
-        let mut solutions = Vec::new();
+    /// Runs `iterations` more iterations without tracing and returns the best
+    /// solution found so far, `None` if no ant completed a solution yet.
+    pub fn solve(&mut self, iterations: usize) -> Option<Solution> {
+        for _ in 0..iterations {
+            self.run(&mut NoopObserver)
+                .expect("NoopObserver never fails");
+        }
+
+        self.best.clone()
+    }
 
-        for ant in 0..self.size {
-            let solution = self.build_solution(ant, out)?;
-            solutions.push(solution);
+    /// Runs iterations reporting every step to `observer` until `termination`
+    /// fires, and returns the criterion that did.
+    pub fn run_until<O>(
+        &mut self,
+        termination: &Termination,
+        observer: &mut O,
+    ) -> Result<Termination, Error>
+    where
+        O: Observer + ?Sized,
+    {
+        self.run_until_with(termination, observer, |_| {})
+    }
+
+    /// Like [`run_until`](Self::run_until), calling `after_iteration` once
+    /// every iteration finished, before `termination` is checked.
+    pub fn run_until_with<O, F>(
+        &mut self,
+        termination: &Termination,
+        observer: &mut O,
+        mut after_iteration: F,
+    ) -> Result<Termination, Error>
+    where
+        O: Observer + ?Sized,
+        F: FnMut(&AntSystem),
+    {
+        let start = Instant::now();
+        loop {
+            self.run(observer)?;
+            after_iteration(self);
+            if let Some(fired) = termination.check(self, start.elapsed()) {
+                return Ok(fired);
+            }
         }
+    }
+
+    /// Runs one iteration reporting every step to `observer`, and returns the
+    /// solution of every ant that didn't fail with its cost.
+    pub fn run<O>(&mut self, observer: &mut O) -> Result<Vec<(Vec<usize>, f64)>, Error>
+    where
+        O: Observer + ?Sized,
+    {
+        self.iteration += 1;
+        let started = *self.started.get_or_insert_with(Instant::now);
+        observer.iteration_started(self.iteration, self)?;
+        self.refresh_choice_info();
+
+        let seeds: Vec<u64> = (0..self.size).map(|_| self.rng.gen()).collect();
+        let mut built = if self.parallel {
+            let paths = seeds
+                .par_iter()
+                .enumerate()
+                .map(|(ant, &seed)| {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    self.build_solution(ant, &mut rng, &mut NoopObserver)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
 
-        let mut solutions_to_return = Vec::new();
-        for (ant, solution) in solutions.iter().enumerate() {
-            let cost = compute_cost(&solution, &self.distances);
-            writeln!(
-                out,
-                "Hormiga {}: {} (costo: {})",
-                ant + 1,
-                solution.to_display_path()?,
-                cost
-            )?;
-            solutions_to_return.push((solution.clone(), cost));
+            for ant in 0..self.size {
+                observer.ant_started(ant, self.initial)?;
+            }
+
+            paths.into_iter()
+        } else {
+            Vec::new().into_iter()
+        };
+
+        let mut solutions = Vec::new();
+        let mut ants = Vec::new();
+        for (ant, &seed) in seeds.iter().enumerate() {
+            let solution = match built.next() {
+                Some(path) => path,
+                None => {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    self.build_solution(ant, &mut rng, observer)?
+                }
+            };
+
+            let solution = match solution {
+                Some(solution) => solution,
+                None => continue,
+            };
+
+            if let Variant::ColonySystem(props) = &self.variant {
+                let xi = props.xi;
+                for (from, to) in solution_edges(&solution, self.mode) {
+                    self.local_update(from, to, xi, observer)?;
+                }
+            }
+
+            let cost = compute_cost(&solution, &self.distances, self.mode);
+            observer.tour_finished(ant, &solution, cost)?;
+            solutions.push((solution, cost));
+            ants.push(ant);
         }
 
-        self.update_pheromones(&solutions, out)?;
+        self.refresh_neighbours();
+        self.improve(&mut solutions, &ants, observer)?;
+        self.update_best(&solutions);
+        self.update_pheromones(&solutions, observer)?;
+        self.record_stats(&solutions, started);
+
+        observer.iteration_finished(self.iteration, &solutions, self.best.as_ref())?;
 
-        Ok(solutions_to_return)
+        Ok(solutions)
     }
 }
 
 impl AntSystem {
-    fn build_solution<W: Write>(&self, i: usize, out: &mut W) -> Result<Vec<usize>, Error> {
+    /// `ants` holds the number of the ant that built each solution.
+    fn improve<O>(
+        &self,
+        solutions: &mut [(Vec<usize>, f64)],
+        ants: &[usize],
+        observer: &mut O,
+    ) -> Result<(), Error>
+    where
+        O: Observer + ?Sized,
+    {
+        let local_search = match &self.local_search {
+            Some(local_search) => local_search,
+            None => return Ok(()),
+        };
+
+        let improved: Vec<usize> = match local_search.target {
+            Target::EveryAnt => (0..solutions.len()).collect(),
+            Target::IterationBest => solutions
+                .iter()
+                .enumerate()
+                .min_by(|(_, (_, a)), (_, (_, b))| a.partial_cmp(b).unwrap())
+                .map(|(ant, _)| ant)
+                .into_iter()
+                .collect(),
+        };
+
+        for i in improved {
+            let (path, cost) = &mut solutions[i];
+            local_search.improve(path, &self.distances, &self.neighbours, self.mode);
+
+            let improved = compute_cost(path, &self.distances, self.mode);
+            if improved < *cost {
+                *cost = improved;
+                observer.tour_improved(ants[i], path, improved)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_stats(&mut self, solutions: &[(Vec<usize>, f64)], started: Instant) {
+        let costs: Vec<f64> = solutions.iter().map(|(_, cost)| *cost).collect();
+        let count = costs.len() as f64;
+        let mean = costs.iter().sum::<f64>() / count;
+        let variance = costs.iter().map(|cost| (cost - mean).powi(2)).sum::<f64>() / count;
+        let (best, worst) = if costs.is_empty() {
+            (f64::NAN, f64::NAN)
+        } else {
+            let best = costs.iter().cloned().fold(f64::INFINITY, f64::min);
+            let worst = costs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            (best, worst)
+        };
+
+        let (mut min, mut max, mut sum, mut edges) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0);
+        for ((r, c), &pheromone) in self.pheromones.indexed_iter() {
+            if r != c && self.distances[[r, c]].is_finite() {
+                min = min.min(pheromone);
+                max = max.max(pheromone);
+                sum += pheromone;
+                edges += 1;
+            }
+        }
+
+        let best_so_far = match &self.best {
+            Some(best) => best.cost,
+            None => f64::NAN,
+        };
+        let stats = IterationStats {
+            iteration: self.iteration,
+            failed_ants: self.size - solutions.len(),
+            best,
+            mean,
+            worst,
+            std_dev: variance.sqrt(),
+            best_so_far,
+            pheromone_min: min,
+            pheromone_max: max,
+            pheromone_mean: sum / edges as f64,
+            branching_factor: self.branching_factor(BRANCHING_LAMBDA),
+            elapsed: started.elapsed(),
+        };
+
+        self.history.iterations.push(stats);
+    }
+
+    fn update_best(&mut self, solutions: &[(Vec<usize>, f64)]) {
+        let iteration_best = solutions
+            .iter()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+        if let Some((path, cost)) = iteration_best {
+            let improved = match &self.best {
+                Some(best) => *cost < best.cost,
+                None => true,
+            };
+            if improved {
+                self.best = Some(Solution {
+                    path: path.clone(),
+                    cost: *cost,
+                    iteration: self.iteration,
+                });
+            }
+        }
+    }
+
+    /// Builds the solution of `ant`, or returns `None` if it failed at a
+    /// dead end.
+    fn build_solution<O>(
+        &self,
+        ant: usize,
+        rng: &mut StdRng,
+        observer: &mut O,
+    ) -> Result<Option<Vec<usize>>, Error>
+    where
+        O: Observer + ?Sized,
+    {
         let no_cities = self.visibility.shape()[0];
 
         let mut visited = Vec::new();
+        let mut is_visited = vec![false; no_cities];
         visited.push(self.initial);
+        is_visited[self.initial] = true;
 
-        writeln!(out, "Hormiga {}", i + 1)?;
-        writeln!(out, "Ciudad inicial: {}", self.initial.to_char_index())?;
-        while visited.len() != no_cities {
-            let mut probs = Vec::new();
+        // Cities already tried from every city of the path, for backtracking.
+        let mut tried = vec![Vec::new()];
+        let mut backtracks = 0;
+
+        observer.ant_started(ant, self.initial)?;
+        loop {
             let curr = *visited.last().expect("No cities visited?");
+            let reachable = |city: usize| {
+                !is_visited[city]
+                    && self.distances[[curr, city]].is_finite()
+                    && !tried[tried.len() - 1].contains(&city)
+            };
 
-            let sum =
-                (0..no_cities)
-                    .filter(|city| !visited.contains(city))
-                    .fold(0.0, |acc, city| {
-                        let pheromone = self.pheromones[[curr, city]];
-                        let visibility = self.visibility[[curr, city]];
+            let mut candidates: Vec<_> = match self.candidate_lists.get(curr) {
+                Some(list) => list
+                    .iter()
+                    .filter(|&&city| reachable(city))
+                    .map(|&city| self.candidate(curr, city))
+                    .collect(),
+                None => Vec::new(),
+            };
 
-                        acc + pheromone.powf(self.alpha) * visibility.powf(self.beta)
-                    });
+            let fallback = candidates.is_empty() && !self.candidate_lists.is_empty();
+            if candidates.is_empty() {
+                candidates = (0..no_cities)
+                    .filter(|&city| reachable(city))
+                    .map(|city| self.candidate(curr, city))
+                    .collect();
+            }
 
-            for city in 0..no_cities {
-                if visited.contains(&city) {
-                    continue;
+            if candidates.is_empty() {
+                if visited.len() == no_cities
+                    && (self.mode == Mode::OpenPath
+                        || self.distances[[curr, self.initial]].is_finite())
+                {
+                    return Ok(Some(visited));
                 }
 
-                let pheromone = self.pheromones[[curr, city]].powf(self.alpha);
-                let visibility = self.visibility[[curr, city]].powf(self.beta);
-                let prod = pheromone * visibility;
-                let prob = prod / sum;
+                observer.dead_end(ant, &visited, self.dead_end)?;
+                match self.dead_end {
+                    DeadEnd::Backtrack if visited.len() > 1 && backtracks < no_cities => {
+                        backtracks += 1;
+                        let city = visited.pop().expect("More than one city visited");
+                        is_visited[city] = false;
+                        tried.pop();
+                        tried.last_mut().expect("Initial city kept").push(city);
+                        continue;
+                    }
+                    DeadEnd::Repair => {
+                        if let Some(repaired) = self.repair(visited, &is_visited) {
+                            return Ok(Some(repaired));
+                        }
+                    }
+                    _ => {}
+                }
 
-                writeln!(
-                    out,
-                    "{} -> {}: 𝜏^𝛼 = {}, 𝜂^𝛽 = {}, (𝜏^𝛼) * (𝜂^𝛽) = {}",
-                    curr.to_char_index(),
-                    city.to_char_index(),
-                    pheromone,
-                    visibility,
-                    prod
-                )?;
+                observer.ant_failed(ant)?;
+                return Ok(None);
+            }
 
-                probs.push((city, prob));
+            let sum: f64 = candidates.iter().map(|candidate| candidate.weight).sum();
+            for candidate in &mut candidates {
+                candidate.probability = candidate.weight / sum;
             }
 
-            writeln!(out, "Suma: {}", sum)?;
+            observer.candidates(ant, curr, &candidates, sum)?;
 
-            for (city, prob) in &probs {
-                writeln!(
-                    out,
-                    "{} -> {}: prob = {}",
-                    curr.to_char_index(),
-                    city.to_char_index(),
-                    prob
-                )?;
-            }
+            let (choosen, choice) = if fallback {
+                (best_candidate(&candidates).city, Choice::Fallback)
+            } else {
+                self.choose(&candidates, rng)
+            };
+            observer.city_chosen(ant, choosen, choice)?;
+            visited.push(choosen);
+            is_visited[choosen] = true;
+            tried.push(Vec::new());
+        }
+    }
+
+    /// Completes a path stuck at a dead end by cheapest insertion of the
+    /// cities it's missing, see [`DeadEnd::Repair`].
+    fn repair(&self, mut path: Vec<usize>, is_visited: &[bool]) -> Option<Vec<usize>> {
+        let d = &self.distances;
+        let mut remaining: Vec<_> = (0..is_visited.len())
+            .filter(|&city| !is_visited[city])
+            .collect();
+
+        // Every city was visited but the last one has no edge back, it's
+        // moved somewhere else.
+        if remaining.is_empty() {
+            remaining.extend(path.pop());
+        }
+
+        while !remaining.is_empty() {
+            let mut best: Option<(usize, usize, f64)> = None;
+            for (i, &city) in remaining.iter().enumerate() {
+                for pos in 1..=path.len() {
+                    let prev = path[pos - 1];
+                    let next = match (path.get(pos), self.mode) {
+                        (Some(&next), _) => Some(next),
+                        (None, Mode::ClosedTour) => Some(path[0]),
+                        (None, Mode::OpenPath) => None,
+                    };
 
-            let rand = thread_rng().gen_range(0., 1.);
-            writeln!(out, "Número aleatorio: {}", rand)?;
+                    // An edge back to the start that doesn't exist yet gives
+                    // an infinite saving, so fixing it comes first.
+                    let (increase, fits) = match next {
+                        Some(next) => (
+                            d[[prev, city]] + d[[city, next]] - d[[prev, next]],
+                            d[[prev, city]].is_finite() && d[[city, next]].is_finite(),
+                        ),
+                        None => (d[[prev, city]], d[[prev, city]].is_finite()),
+                    };
+                    let cheaper = match best {
+                        Some((_, _, best)) => increase < best,
+                        None => true,
+                    };
 
-            let (mut choosen, mut acc) = probs[0];
-            for i in 0..probs.len() {
-                if rand < acc || i == probs.len() - 1 {
-                    choosen = probs[i].0;
-                    break;
+                    if fits && cheaper {
+                        best = Some((i, pos, increase));
+                    }
                 }
+            }
+
+            let (i, pos, _) = best?;
+            path.insert(pos, remaining.swap_remove(i));
+        }
+
+        if compute_cost(&path, d, self.mode).is_finite() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    fn candidate(&self, from: usize, city: usize) -> Candidate {
+        Candidate {
+            city,
+            pheromone: self.trail[[from, city]],
+            visibility: self.heuristic[[from, city]],
+            weight: self.choice_info[[from, city]],
+            probability: 0.0,
+        }
+    }
 
-                acc += probs[i + 1].1;
+    /// The local search is public and may be set or changed after the colony
+    /// was built, the neighbour lists follow it.
+    fn refresh_neighbours(&mut self) {
+        if let Some(local_search) = &self.local_search {
+            let length = local_search.neighbours.min(self.distances.nrows() - 1);
+            if self.neighbours.first().map(Vec::len) != Some(length) {
+                self.neighbours = nearest_neighbours(&self.distances, length);
             }
+        }
+    }
 
-            writeln!(out, "Siguiente ciudad: {}\n", choosen.to_char_index())?;
-            visited.push(choosen);
+    fn refresh_choice_info(&mut self) {
+        if self.heuristic_beta != self.beta {
+            let beta = self.beta;
+            self.heuristic = self.visibility.mapv(|visibility| visibility.powf(beta));
+            self.heuristic_beta = beta;
         }
 
-        writeln!(
-            out,
-            "Camino de la hormiga {}: {}\n---\n",
-            i + 1,
-            visited.to_display_path()?
-        )?;
+        let alpha = self.alpha;
+        Zip::from(&mut self.trail)
+            .and(&mut self.choice_info)
+            .and(&self.pheromones)
+            .and(&self.heuristic)
+            .apply(|trail, choice_info, &pheromone, &heuristic| {
+                *trail = pheromone.powf(alpha);
+                *choice_info = *trail * heuristic;
+            });
+    }
+
+    /// Keeps the choice information of the edge between `from` and `to` in
+    /// sync with a pheromone change made during the iteration.
+    fn refresh_edge(&mut self, from: usize, to: usize) {
+        let directions: &[_] = if self.asymmetric {
+            &[(from, to)]
+        } else {
+            &[(from, to), (to, from)]
+        };
 
-        Ok(visited)
+        for &(r, c) in directions {
+            self.trail[[r, c]] = self.pheromones[[r, c]].powf(self.alpha);
+            self.choice_info[[r, c]] = self.trail[[r, c]] * self.heuristic[[r, c]];
+        }
     }
 
-    fn update_pheromones<W: Write>(
+    fn choose(&self, candidates: &[Candidate], rng: &mut StdRng) -> (usize, Choice) {
+        if let Variant::ColonySystem(props) = &self.variant {
+            let q0 = props.q0;
+            let draw = rng.gen_range(0., 1.);
+            if draw < q0 {
+                return (best_candidate(candidates).city, Choice::Greedy(draw));
+            }
+        }
+
+        let rand = rng.gen_range(0., 1.);
+
+        let mut choosen = candidates[0].city;
+        let mut acc = candidates[0].probability;
+        for i in 0..candidates.len() {
+            if rand < acc || i == candidates.len() - 1 {
+                choosen = candidates[i].city;
+                break;
+            }
+
+            acc += candidates[i + 1].probability;
+        }
+
+        (choosen, Choice::Roulette(rand))
+    }
+
+    /// Ant Colony System local update, applied on the edges of every ant as
+    /// soon as it finishes its solution.
+    fn local_update<O>(
         &mut self,
-        solutions: &[Vec<usize>],
-        out: &mut W,
-    ) -> Result<(), Error> {
-        let shape = self.pheromones.shape().to_owned();
-        let costs: Vec<_> = solutions
-            .iter()
-            .map(|p| compute_cost(p, &self.distances))
+        from: usize,
+        to: usize,
+        xi: f64,
+        observer: &mut O,
+    ) -> Result<(), Error>
+    where
+        O: Observer + ?Sized,
+    {
+        let decayed = (1.0 - xi) * self.pheromones[[from, to]];
+        let deposit = xi * self.initial_pheromone;
+        let value = decayed + deposit;
+
+        self.pheromones[[from, to]] = value;
+        if !self.asymmetric {
+            self.pheromones[[to, from]] = value;
+        }
+        self.refresh_edge(from, to);
+        observer.pheromone_updated(from, to, decayed, &[deposit], value)
+    }
+
+    /// Ant Colony System global update, only the edges of the best-so-far
+    /// solution evaporate and receive its deposit.
+    fn global_update<O>(&mut self, observer: &mut O) -> Result<(), Error>
+    where
+        O: Observer + ?Sized,
+    {
+        let best = self
+            .best
+            .as_ref()
+            .expect("Best is updated before pheromones");
+        let deposit = (1.0 - self.rho) * self.q / best.cost;
+        let edges = solution_edges(&best.path, self.mode);
+
+        for (r, c) in edges {
+            let evaporation = self.rho * self.pheromones[[r, c]];
+            let value = evaporation + deposit;
+
+            self.pheromones[[r, c]] = value;
+            if !self.asymmetric {
+                self.pheromones[[c, r]] = value;
+            }
+            observer.pheromone_updated(r, c, evaporation, &[deposit], value)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_pheromones<O>(
+        &mut self,
+        solutions: &[(Vec<usize>, f64)],
+        observer: &mut O,
+    ) -> Result<(), Error>
+    where
+        O: Observer + ?Sized,
+    {
+        // Trails stay as they are until some ant completes a solution.
+        if self.best.is_none() {
+            return Ok(());
+        }
+
+        if let Variant::ColonySystem(_) = self.variant {
+            return self.global_update(observer);
+        }
+
+        let depositors: Vec<(Vec<_>, f64)> = self
+            .depositors(solutions)
+            .into_iter()
+            .map(|(p, amount)| {
+                let mut edges = solution_edges(p, self.mode);
+                // A closed tour of two cities crosses its only edge twice.
+                if !self.asymmetric {
+                    edges.dedup_by_key(|&mut (from, to)| (from.min(to), from.max(to)));
+                }
+                (edges, amount)
+            })
             .collect();
 
-        let edges: Vec<Vec<_>> = solutions
+        let limits = match &self.variant {
+            Variant::MaxMin(props) => Some(self.max_min_limits(props)),
+            _ => None,
+        };
+
+        let rho = self.rho;
+        self.pheromones.mapv_inplace(|pheromone| rho * pheromone);
+        let evaporated = if observer.wants_pheromone_updates() {
+            Some(self.pheromones.clone())
+        } else {
+            None
+        };
+
+        for (edges, amount) in &depositors {
+            for &(from, to) in edges {
+                self.pheromones[[from, to]] += amount;
+                if !self.asymmetric {
+                    self.pheromones[[to, from]] += amount;
+                }
+            }
+        }
+
+        if let Some((min, max)) = limits {
+            for ((r, c), pheromone) in self.pheromones.indexed_iter_mut() {
+                if r != c && self.distances[[r, c]].is_finite() {
+                    *pheromone = pheromone.max(min).min(max);
+                }
+            }
+        }
+
+        if let Some(evaporated) = evaporated {
+            self.report_pheromones(&evaporated, &depositors, observer)?;
+        }
+
+        if let Variant::MaxMin(props) = &self.variant {
+            let reinit_after = props.reinit_after;
+            let (_, max) = limits.expect("Limits are computed for MaxMin");
+            self.reset_on_stagnation(reinit_after, max);
+        }
+
+        Ok(())
+    }
+
+    /// Reports the update of every cell, with the deposit of each depositor
+    /// that traveled the edge.
+    fn report_pheromones<O>(
+        &self,
+        evaporated: &Array2<f64>,
+        depositors: &[(Vec<(usize, usize)>, f64)],
+        observer: &mut O,
+    ) -> Result<(), Error>
+    where
+        O: Observer + ?Sized,
+    {
+        let no_cities = self.pheromones.shape()[0];
+        let successors: Vec<Vec<Option<usize>>> = depositors
             .iter()
-            .map(|p| p.windows(2).map(|edge| (edge[0], edge[1])).collect())
+            .map(|(edges, _)| {
+                let mut successor = vec![None; no_cities];
+                for &(from, to) in edges {
+                    successor[from] = Some(to);
+                }
+                successor
+            })
             .collect();
 
-        for r in 0..shape[0] {
-            for c in 0..shape[1] {
-                let evaporation = self.rho * self.pheromones[[r, c]];
-                write!(
-                    out,
-                    "{} -> {}: feromona = {} ",
-                    r.to_char_index(),
-                    c.to_char_index(),
-                    evaporation
-                )?;
-
-                self.pheromones[[r, c]] = evaporation;
-
-                for ant in 0..self.size {
-                    let traveled = edges[ant].contains(&(r, c)) || edges[ant].contains(&(c, r));
-                    if traveled {
-                        let w = self.q / costs[ant];
-                        write!(out, "+ {} ", w)?;
-                        self.pheromones[[r, c]] += w;
-                    } else {
-                        write!(out, "+ 0.0 ")?;
-                    }
+        let mut deposits = vec![0.0; depositors.len()];
+        for ((r, c), &value) in self.pheromones.indexed_iter() {
+            for (i, (_, amount)) in depositors.iter().enumerate() {
+                let traveled = successors[i][r] == Some(c)
+                    || (!self.asymmetric && successors[i][c] == Some(r));
+                deposits[i] = if traveled { *amount } else { 0.0 };
+            }
+
+            observer.pheromone_updated(r, c, evaporated[[r, c]], &deposits, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Solutions that deposit pheromone in this iteration, with the amount
+    /// each one leaves on every edge it uses.
+    fn depositors<'a>(&'a self, solutions: &'a [(Vec<usize>, f64)]) -> Vec<(&'a [usize], f64)> {
+        let best = self
+            .best
+            .as_ref()
+            .expect("Best is updated before pheromones");
+
+        match &self.variant {
+            Variant::AntSystem => solutions
+                .iter()
+                .map(|(p, c)| (p.as_slice(), self.q / c))
+                .collect(),
+            Variant::MaxMin(props) => {
+                let every = props.best_so_far_every;
+                if every > 0 && self.iteration.is_multiple_of(every) {
+                    vec![(best.path.as_slice(), self.q / best.cost)]
+                } else {
+                    // The best-so-far solution stands in when every ant failed.
+                    let (path, cost) = solutions
+                        .iter()
+                        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                        .map(|(path, cost)| (path.as_slice(), *cost))
+                        .unwrap_or((best.path.as_slice(), best.cost));
+                    vec![(path, self.q / cost)]
                 }
+            }
+            Variant::ColonySystem(_) => vec![(best.path.as_slice(), self.q / best.cost)],
+            Variant::Elitist(props) => {
+                let mut depositors: Vec<_> = solutions
+                    .iter()
+                    .map(|(p, c)| (p.as_slice(), self.q / c))
+                    .collect();
 
-                writeln!(out, "= {}", self.pheromones[[r, c]])?;
+                depositors.push((best.path.as_slice(), props.e * self.q / best.cost));
+                depositors
+            }
+            Variant::RankBased(props) => {
+                let mut ranked: Vec<_> = solutions.iter().collect();
+                ranked.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+                let w = props.w;
+                let mut depositors: Vec<_> = ranked
+                    .into_iter()
+                    .take(w.saturating_sub(1))
+                    .enumerate()
+                    .map(|(r, (p, c))| (p.as_slice(), (w - r - 1) as f64 * self.q / c))
+                    .collect();
+
+                depositors.push((best.path.as_slice(), w as f64 * self.q / best.cost));
+                depositors
             }
         }
+    }
 
-        Ok(())
+    /// Returns the trail limits (τ_min, τ_max) derived from the best-so-far cost.
+    fn max_min_limits(&self, props: &MaxMinProps) -> (f64, f64) {
+        let best = self
+            .best
+            .as_ref()
+            .expect("Best is updated before pheromones");
+        let max = self.q / ((1.0 - self.rho) * best.cost);
+
+        let no_cities = self.distances.shape()[0] as f64;
+        let p_dec = props.p_best.powf(1.0 / no_cities);
+        let avg = no_cities / 2.0;
+        let min = (max * (1.0 - p_dec) / ((avg - 1.0) * p_dec)).min(max);
+
+        (min, max)
+    }
+
+    fn reset_on_stagnation(&mut self, reinit_after: Option<usize>, max: f64) {
+        let reinit_after = match reinit_after {
+            Some(reinit_after) => reinit_after,
+            None => return,
+        };
+
+        let best = self
+            .best
+            .as_ref()
+            .expect("Best is updated before pheromones");
+        let since = best.iteration.max(self.last_reset);
+        if self.iteration - since >= reinit_after {
+            self.pheromones = init_pheromone_matrix(&self.distances, max);
+            self.last_reset = self.iteration;
+        }
     }
 }
@@ -0,0 +1,129 @@
+use crate::locale::{Catalog, ENGLISH};
+use crate::system::AntSystem;
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// When a run stops, checked after every iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Termination {
+    /// The colony ran this many iterations.
+    Iterations(usize),
+    /// The run took at least this long.
+    Time(Duration),
+    /// The best-so-far cost is at or below the target.
+    TargetCost(f64),
+    /// The best-so-far solution didn't improve for this many iterations,
+    /// counted from the start while no ant has completed a solution.
+    NoImprovement(usize),
+    /// The average λ-branching factor of the pheromone matrix dropped to
+    /// `threshold`, see [`AntSystem::branching_factor`].
+    Stagnation { lambda: f64, threshold: f64 },
+    /// Every criterion fired.
+    All(Vec<Termination>),
+    /// At least one criterion fired.
+    Any(Vec<Termination>),
+}
+
+impl Termination {
+    /// Returns the criterion that fired, if any. [`Termination::Any`] reports
+    /// the first of its criteria that fired and [`Termination::All`] all of
+    /// them. `elapsed` is the time since the run started.
+    pub fn check(&self, system: &AntSystem, elapsed: Duration) -> Option<Termination> {
+        let fired = match self {
+            Termination::Iterations(iterations) => system.iteration() >= *iterations,
+            Termination::Time(limit) => elapsed >= *limit,
+            Termination::TargetCost(target) => match system.best() {
+                Some(best) => best.cost <= *target,
+                None => false,
+            },
+            Termination::NoImprovement(iterations) => {
+                let found = system.best().map_or(0, |best| best.iteration);
+                system.iteration() - found >= *iterations
+            }
+            Termination::Stagnation { lambda, threshold } => {
+                system.iteration() > 0 && system.branching_factor(*lambda) <= *threshold
+            }
+            Termination::All(criteria) => {
+                let fired: Option<Vec<_>> = criteria
+                    .iter()
+                    .map(|criterion| criterion.check(system, elapsed))
+                    .collect();
+                return fired.map(Termination::All);
+            }
+            Termination::Any(criteria) => {
+                return criteria
+                    .iter()
+                    .find_map(|criterion| criterion.check(system, elapsed));
+            }
+        };
+
+        if fired {
+            Some(self.clone())
+        } else {
+            None
+        }
+    }
+}
+
+impl Termination {
+    /// Describes the criterion with the phrases of `catalog`, [`Display`]
+    /// uses the English one.
+    pub fn localized<'a>(&'a self, catalog: &'a Catalog) -> Localized<'a> {
+        Localized {
+            termination: self,
+            catalog,
+        }
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.localized(&ENGLISH).fmt(f)
+    }
+}
+
+/// A [`Termination`] described in the language of a [`Catalog`].
+#[derive(Debug, Clone, Copy)]
+pub struct Localized<'a> {
+    termination: &'a Termination,
+    catalog: &'a Catalog,
+}
+
+impl Display for Localized<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let catalog = self.catalog;
+        let join = |f: &mut fmt::Formatter<'_>, criteria: &[Termination], op: &str| {
+            for (i, criterion) in criteria.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", op)?;
+                }
+                write!(f, "({})", criterion.localized(catalog))?;
+            }
+            Ok(())
+        };
+
+        match self.termination {
+            Termination::Iterations(iterations) => {
+                write!(f, "{} {}", iterations, catalog.iterations)
+            }
+            Termination::Time(limit) => {
+                write!(f, "{} {}", limit.as_secs_f64(), catalog.seconds_elapsed)
+            }
+            Termination::TargetCost(target) => write!(f, "{} <= {}", catalog.cost, target),
+            Termination::NoImprovement(iterations) => {
+                write!(
+                    f,
+                    "{} {}",
+                    iterations, catalog.iterations_without_improvement
+                )
+            }
+            Termination::Stagnation { lambda, threshold } => write!(
+                f,
+                "{} <= {} (λ = {})",
+                catalog.branching_factor, threshold, lambda
+            ),
+            Termination::All(criteria) => join(f, criteria, catalog.and),
+            Termination::Any(criteria) => join(f, criteria, catalog.or),
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::{anyhow, bail, Context, Error};
+use ndarray::Array2;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EdgeWeightType {
+    Euc2d,
+    Ceil2d,
+    Geo,
+    Att,
+    Explicit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EdgeWeightFormat {
+    FullMatrix,
+    UpperRow,
+    LowerDiagRow,
+}
+
+/// A problem instance read from a TSPLIB file.
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub name: String,
+    pub comment: Option<String>,
+    pub dimension: usize,
+    /// Whether the file is an asymmetric instance (`TYPE: ATSP`).
+    pub asymmetric: bool,
+    /// Edges written as `-` in the `EDGE_WEIGHT_SECTION` are missing and
+    /// have an infinite distance.
+    pub distances: Array2<f64>,
+    /// City names from the `NODE_NAME_SECTION`, an extension to TSPLIB
+    /// where every line holds a node number followed by its name.
+    pub names: Option<Vec<String>>,
+}
+
+/// Reads and parses the TSPLIB file at `path`.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Instance, Error> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Couldn't read {}", path.display()))?;
+
+    parse(&contents).with_context(|| format!("Invalid TSPLIB file {}", path.display()))
+}
+
+/// Parses the contents of a TSPLIB `.tsp` or `.atsp` file.
+pub fn parse(contents: &str) -> Result<Instance, Error> {
+    let mut name = None;
+    let mut asymmetric = false;
+    let mut names = None;
+    let mut comment = None;
+    let mut dimension = None;
+    let mut weight_type = None;
+    let mut weight_format = None;
+    let mut coords = None;
+    let mut weights = None;
+
+    let mut lines = contents.lines().map(str::trim).peekable();
+    while let Some(line) = lines.next() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = match line.find(':') {
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => (line, ""),
+        };
+
+        match key {
+            "NAME" => name = Some(value.to_owned()),
+            "COMMENT" => comment = Some(value.to_owned()),
+            "TYPE" => {
+                asymmetric = match value {
+                    "TSP" => false,
+                    "ATSP" => true,
+                    other => bail!("Unsupported problem type {}", other),
+                }
+            }
+            "DIMENSION" => {
+                let value = value
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid dimension {}", value))?;
+                if value < 2 {
+                    bail!(
+                        "Dimension {} is too small, at least 2 cities are needed",
+                        value
+                    );
+                }
+                dimension = Some(value);
+            }
+            "EDGE_WEIGHT_TYPE" => {
+                weight_type = Some(match value {
+                    "EUC_2D" => EdgeWeightType::Euc2d,
+                    "CEIL_2D" => EdgeWeightType::Ceil2d,
+                    "GEO" => EdgeWeightType::Geo,
+                    "ATT" => EdgeWeightType::Att,
+                    "EXPLICIT" => EdgeWeightType::Explicit,
+                    other => bail!("Unsupported edge weight type {}", other),
+                })
+            }
+            "EDGE_WEIGHT_FORMAT" => {
+                weight_format = Some(match value {
+                    "FULL_MATRIX" => EdgeWeightFormat::FullMatrix,
+                    "UPPER_ROW" => EdgeWeightFormat::UpperRow,
+                    "LOWER_DIAG_ROW" => EdgeWeightFormat::LowerDiagRow,
+                    other => bail!("Unsupported edge weight format {}", other),
+                })
+            }
+            "NODE_COORD_SECTION" => coords = Some(read_numbers(&mut lines, false)?),
+            "EDGE_WEIGHT_SECTION" => weights = Some(read_numbers(&mut lines, true)?),
+            "NODE_NAME_SECTION" => names = Some(read_names(&mut lines)?),
+            "DISPLAY_DATA_SECTION" => {
+                read_numbers(&mut lines, false)?;
+            }
+            "EOF" => break,
+            _ => {}
+        }
+    }
+
+    let name = name.unwrap_or_default();
+    let dimension = dimension.ok_or_else(|| anyhow!("Missing DIMENSION"))?;
+    let weight_type = weight_type.ok_or_else(|| anyhow!("Missing EDGE_WEIGHT_TYPE"))?;
+
+    let distances = match weight_type {
+        EdgeWeightType::Explicit => {
+            let format = weight_format.ok_or_else(|| anyhow!("Missing EDGE_WEIGHT_FORMAT"))?;
+            let weights = weights.ok_or_else(|| anyhow!("Missing EDGE_WEIGHT_SECTION"))?;
+            if asymmetric && format != EdgeWeightFormat::FullMatrix {
+                bail!("Asymmetric instances need a FULL_MATRIX of edge weights");
+            }
+            explicit_matrix(dimension, format, &weights)?
+        }
+        weight_type => {
+            let coords = coords.ok_or_else(|| anyhow!("Missing NODE_COORD_SECTION"))?;
+            let points = node_coords(dimension, &coords)?;
+            coords_matrix(weight_type, &points)
+        }
+    };
+
+    let names = match names {
+        Some(names) => Some(node_names(dimension, names)?),
+        None => None,
+    };
+
+    Ok(Instance {
+        name,
+        comment,
+        dimension,
+        asymmetric,
+        distances,
+        names,
+    })
+}
+
+fn starts_with_keyword(line: &str) -> bool {
+    line.chars()
+        .next()
+        .map(|c| c.is_ascii_alphabetic())
+        .unwrap_or(false)
+}
+
+/// Reads numbers until the next keyword. With `missing_edges` a `-` token
+/// is read as infinity, an edge that doesn't exist.
+fn read_numbers<'a, I>(
+    lines: &mut std::iter::Peekable<I>,
+    missing_edges: bool,
+) -> Result<Vec<f64>, Error>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut numbers = Vec::new();
+
+    while let Some(line) = lines.peek() {
+        if starts_with_keyword(line) {
+            break;
+        }
+
+        for token in line.split_whitespace() {
+            let number = match token {
+                "-" if missing_edges => f64::INFINITY,
+                token => token
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid number {}", token))?,
+            };
+            numbers.push(number);
+        }
+
+        lines.next();
+    }
+
+    Ok(numbers)
+}
+
+fn read_names<'a, I>(lines: &mut std::iter::Peekable<I>) -> Result<Vec<(usize, String)>, Error>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut names = Vec::new();
+
+    while let Some(line) = lines.peek() {
+        if starts_with_keyword(line) {
+            break;
+        }
+
+        if !line.is_empty() {
+            let (node, name) = match line.find(char::is_whitespace) {
+                Some(pos) => (&line[..pos], line[pos..].trim()),
+                None => bail!("Missing name of node {}", line),
+            };
+
+            let node = node
+                .parse::<usize>()
+                .with_context(|| format!("Invalid node number {}", node))?;
+            names.push((node, name.to_owned()));
+        }
+
+        lines.next();
+    }
+
+    Ok(names)
+}
+
+fn node_names(dimension: usize, entries: Vec<(usize, String)>) -> Result<Vec<String>, Error> {
+    if entries.len() != dimension {
+        bail!(
+            "Expected {} nodes in NODE_NAME_SECTION, found {}",
+            dimension,
+            entries.len()
+        );
+    }
+
+    let mut names = vec![None; dimension];
+    for (node, name) in entries {
+        match names.get_mut(node.wrapping_sub(1)) {
+            Some(slot @ None) => *slot = Some(name),
+            Some(Some(_)) => bail!("Node {} is named twice", node),
+            None => bail!("Node {} is out of range in NODE_NAME_SECTION", node),
+        }
+    }
+
+    Ok(names
+        .into_iter()
+        .map(|name| name.expect("Every node counted"))
+        .collect())
+}
+
+fn node_coords(dimension: usize, numbers: &[f64]) -> Result<Vec<(f64, f64)>, Error> {
+    if numbers.len() != dimension * 3 {
+        bail!(
+            "Expected {} nodes in NODE_COORD_SECTION, found {} values",
+            dimension,
+            numbers.len()
+        );
+    }
+
+    let mut points = vec![None; dimension];
+    for node in numbers.chunks(3) {
+        let id = node[0];
+        let slot = if id.fract() == 0.0 && id >= 1.0 {
+            points.get_mut(id as usize - 1)
+        } else {
+            None
+        };
+
+        match slot {
+            Some(slot @ None) => *slot = Some((node[1], node[2])),
+            Some(Some(_)) => bail!("Node {} has coordinates twice", id),
+            None => bail!("Node {} is out of range in NODE_COORD_SECTION", id),
+        }
+    }
+
+    Ok(points
+        .into_iter()
+        .map(|point| point.expect("Every node counted"))
+        .collect())
+}
+
+fn explicit_matrix(
+    dimension: usize,
+    format: EdgeWeightFormat,
+    weights: &[f64],
+) -> Result<Array2<f64>, Error> {
+    let expected = match format {
+        EdgeWeightFormat::FullMatrix => dimension * dimension,
+        EdgeWeightFormat::UpperRow => dimension * (dimension - 1) / 2,
+        EdgeWeightFormat::LowerDiagRow => dimension * (dimension + 1) / 2,
+    };
+
+    if weights.len() != expected {
+        bail!(
+            "Expected {} values in EDGE_WEIGHT_SECTION, found {}",
+            expected,
+            weights.len()
+        );
+    }
+
+    let mut matrix = Array2::zeros((dimension, dimension));
+    let mut weights = weights.iter();
+    for r in 0..dimension {
+        let columns = match format {
+            EdgeWeightFormat::FullMatrix => 0..dimension,
+            EdgeWeightFormat::UpperRow => (r + 1)..dimension,
+            EdgeWeightFormat::LowerDiagRow => 0..(r + 1),
+        };
+
+        for c in columns {
+            let weight = *weights.next().expect("Weights already counted");
+            matrix[[r, c]] = weight;
+            if format != EdgeWeightFormat::FullMatrix {
+                matrix[[c, r]] = weight;
+            }
+        }
+    }
+
+    Ok(matrix)
+}
+
+fn coords_matrix(weight_type: EdgeWeightType, points: &[(f64, f64)]) -> Array2<f64> {
+    let distance: fn((f64, f64), (f64, f64)) -> f64 = match weight_type {
+        EdgeWeightType::Euc2d => euc_2d,
+        EdgeWeightType::Ceil2d => ceil_2d,
+        EdgeWeightType::Geo => geo,
+        EdgeWeightType::Att => att,
+        EdgeWeightType::Explicit => unreachable!("Explicit weights have no coordinates"),
+    };
+
+    let geo_points: Vec<_>;
+    let points = if weight_type == EdgeWeightType::Geo {
+        geo_points = points
+            .iter()
+            .map(|&(x, y)| (to_radians(x), to_radians(y)))
+            .collect();
+        &geo_points
+    } else {
+        points
+    };
+
+    Array2::from_shape_fn((points.len(), points.len()), |(i, j)| {
+        if i == j {
+            0.0
+        } else {
+            distance(points[i], points[j])
+        }
+    })
+}
+
+fn nint(x: f64) -> f64 {
+    (x + 0.5).floor()
+}
+
+fn euclidean(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let xd = a.0 - b.0;
+    let yd = a.1 - b.1;
+    (xd * xd + yd * yd).sqrt()
+}
+
+fn euc_2d(a: (f64, f64), b: (f64, f64)) -> f64 {
+    nint(euclidean(a, b))
+}
+
+fn ceil_2d(a: (f64, f64), b: (f64, f64)) -> f64 {
+    euclidean(a, b).ceil()
+}
+
+fn att(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let xd = a.0 - b.0;
+    let yd = a.1 - b.1;
+    let r = ((xd * xd + yd * yd) / 10.0).sqrt();
+    let t = nint(r);
+
+    if t < r {
+        t + 1.0
+    } else {
+        t
+    }
+}
+
+// TSPLIB encodes geographical coordinates as DDD.MM (degrees and minutes)
+// and uses a truncated value of pi, both are kept to match published optima.
+#[allow(clippy::approx_constant)]
+fn to_radians(x: f64) -> f64 {
+    const PI: f64 = 3.141592;
+
+    let deg = x.trunc();
+    let min = x - deg;
+    PI * (deg + 5.0 * min / 3.0) / 180.0
+}
+
+fn geo(a: (f64, f64), b: (f64, f64)) -> f64 {
+    const RRR: f64 = 6378.388;
+
+    let (lat_a, lon_a) = a;
+    let (lat_b, lon_b) = b;
+
+    let q1 = (lon_a - lon_b).cos();
+    let q2 = (lat_a - lat_b).cos();
+    let q3 = (lat_a + lat_b).cos();
+
+    (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
@@ -1,45 +1,49 @@
 use ndarray::Array2;
 use prettytable::{cell, format::consts::FORMAT_BOX_CHARS, Row, Table};
 
-pub trait ToCharIndex {
-    fn to_char_index(&self) -> char;
+/// How cities are named in paths, matrices and traces.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CityLabels {
+    /// Spreadsheet style letters: A to Z, then AA, AB and so on.
+    #[default]
+    Letters,
+    /// The index of the city, from 0.
+    Numbers,
+    /// One name per city, usually read from the instance file.
+    Names(Vec<String>),
 }
 
-impl ToCharIndex for usize {
-    fn to_char_index(&self) -> char {
-        let offset = *self as u8;
-        (b'A' + offset) as char
-    }
-}
-
-pub trait ToDisplayPath {
-    fn to_display_path(&self) -> Result<String, std::fmt::Error>;
-}
-
-impl<T: ToCharIndex> ToDisplayPath for Vec<T> {
-    fn to_display_path(&self) -> Result<String, std::fmt::Error> {
-        use std::fmt::Write;
-
-        let mut out = String::new();
-        write!(out, "[")?;
-        for i in self.iter().take(self.len() - 1) {
-            write!(out, "{}, ", i.to_char_index())?;
-        }
-
-        if let Some(last) = self.last() {
-            write!(out, "{}", last.to_char_index())?;
+impl CityLabels {
+    pub fn label(&self, city: usize) -> String {
+        match self {
+            CityLabels::Letters => {
+                let mut letters = Vec::new();
+                let mut rest = city + 1;
+                while rest > 0 {
+                    rest -= 1;
+                    letters.push(b'A' + (rest % 26) as u8);
+                    rest /= 26;
+                }
+
+                letters.iter().rev().map(|&letter| letter as char).collect()
+            }
+            CityLabels::Numbers => city.to_string(),
+            CityLabels::Names(names) => names[city].clone(),
         }
+    }
 
-        write!(out, "]")?;
-        Ok(out)
+    /// Labels of every city in `path`, as in `[A, C, B]`.
+    pub fn path(&self, path: &[usize]) -> String {
+        let labels: Vec<_> = path.iter().map(|&city| self.label(city)).collect();
+        format!("[{}]", labels.join(", "))
     }
 }
 
-pub fn pretty_matrix(matrix: &Array2<f64>, digits: usize) -> Table {
+pub fn pretty_matrix(matrix: &Array2<f64>, digits: usize, labels: &CityLabels) -> Table {
     let mut table = Table::new();
     table.set_format(*FORMAT_BOX_CHARS);
 
-    let mut titles: Row = (0..matrix.shape()[1]).map(|v| v.to_char_index()).into();
+    let mut titles: Row = (0..matrix.shape()[1]).map(|v| labels.label(v)).into();
     titles.insert_cell(0, cell![""]);
     table.set_titles(titles);
 
@@ -50,7 +54,7 @@ pub fn pretty_matrix(matrix: &Array2<f64>, digits: usize) -> Table {
             .map(|v| format!("{1:.0$}", digits, v))
             .into();
 
-        row.insert_cell(0, cell![r.to_char_index()]);
+        row.insert_cell(0, cell![labels.label(r)]);
         table.add_row(row);
     }
 
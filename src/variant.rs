@@ -0,0 +1,90 @@
+/// Pheromone update rule used by an [`AntSystem`](crate::AntSystem).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Variant {
+    /// Classic Ant System, every ant deposits `q / cost` on its edges.
+    #[default]
+    AntSystem,
+    /// Max-Min Ant System, see [`MaxMinProps`].
+    MaxMin(MaxMinProps),
+    /// Ant Colony System, see [`ColonyProps`].
+    ColonySystem(ColonyProps),
+    /// Elitist Ant System, see [`ElitistProps`].
+    Elitist(ElitistProps),
+    /// Rank-based Ant System, see [`RankProps`].
+    RankBased(RankProps),
+}
+
+/// Parameters of the Max-Min Ant System.
+///
+/// Only one solution deposits per iteration and trails are kept within
+/// [τ_min, τ_max], where τ_max = q / ((1 - 𝜌) · best cost). Trails start at an
+/// estimate of τ_max computed from a nearest neighbour solution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaxMinProps {
+    /// Probability of building the best solution once every trail has
+    /// converged to a limit, used to derive τ_min from τ_max.
+    pub p_best: f64,
+    /// The best-so-far solution deposits every this many iterations, the
+    /// iteration best does otherwise. Zero means only the iteration best.
+    pub best_so_far_every: usize,
+    /// Trails are reset to τ_max after this many iterations without
+    /// improving the best-so-far solution.
+    pub reinit_after: Option<usize>,
+}
+
+impl Default for MaxMinProps {
+    fn default() -> Self {
+        Self {
+            p_best: 0.05,
+            best_so_far_every: 0,
+            reinit_after: None,
+        }
+    }
+}
+
+/// Parameters of the Ant Colony System.
+///
+/// Ants take the candidate with the largest weight with probability `q0` and
+/// use the roulette wheel otherwise. Every crossed edge decays towards the
+/// initial trail τ0 = q / (n · nearest neighbour cost), and at the end of the
+/// iteration only the edges of the best-so-far solution evaporate and
+/// receive its deposit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColonyProps {
+    /// Probability of exploiting the best candidate instead of exploring.
+    pub q0: f64,
+    /// Decay of the local pheromone update (𝜉).
+    pub xi: f64,
+}
+
+impl Default for ColonyProps {
+    fn default() -> Self {
+        Self { q0: 0.9, xi: 0.1 }
+    }
+}
+
+/// Parameters of the Elitist Ant System.
+///
+/// Every ant deposits as in the classic Ant System and the best-so-far
+/// solution adds `e · q / cost` on its edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElitistProps {
+    /// Weight of the best-so-far solution, usually the number of cities.
+    pub e: f64,
+}
+
+/// Parameters of the Rank-based Ant System.
+///
+/// Ants are ranked by cost and only the best `w - 1` deposit, the one ranked
+/// `r` (from 1) with weight `w - r`. The best-so-far solution deposits with
+/// weight `w`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankProps {
+    pub w: usize,
+}
+
+impl Default for RankProps {
+    fn default() -> Self {
+        Self { w: 6 }
+    }
+}
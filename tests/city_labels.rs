@@ -0,0 +1,50 @@
+use ant_system::tsplib;
+use ant_system::CityLabels;
+
+#[test]
+fn letters_continue_past_z() {
+    let labels = CityLabels::Letters;
+    let cases = [
+        (0, "A"),
+        (25, "Z"),
+        (26, "AA"),
+        (27, "AB"),
+        (51, "AZ"),
+        (52, "BA"),
+        (701, "ZZ"),
+        (702, "AAA"),
+    ];
+
+    for &(city, label) in &cases {
+        assert_eq!(labels.label(city), label);
+    }
+
+    assert_eq!(labels.path(&[0, 26, 1]), "[A, AA, B]");
+}
+
+#[test]
+fn names_are_read_from_the_instance() {
+    let instance = tsplib::parse(
+        "NAME : named
+TYPE : TSP
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+1 2
+3
+NODE_NAME_SECTION
+2 San Isidro
+1 Lima
+3 Callao
+EOF",
+    )
+    .unwrap();
+
+    let names = instance.names.unwrap();
+    assert_eq!(names, ["Lima", "San Isidro", "Callao"]);
+    assert_eq!(
+        CityLabels::Names(names).path(&[1, 2]),
+        "[San Isidro, Callao]"
+    );
+}
@@ -0,0 +1,115 @@
+use ant_system::{
+    tsplib, AntProps, AntSystem, DeadEnd, MaxMinProps, Mode, NoopObserver, Termination, Variant,
+};
+
+/// A ring of six cities with two chords, a tour has to follow the ring but
+/// the chords lead ants into dead ends.
+const RING: &str = "NAME : ring
+TYPE : TSP
+DIMENSION : 6
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+1 1 - - 1
+1 - 1 -
+1 - -
+1 -
+1
+EOF";
+
+/// Three cities on a line, there is no closed tour.
+const LINE: &str = "NAME : line
+TYPE : TSP
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+1 -
+1
+EOF";
+
+fn ant_system(instance: &str, dead_end: DeadEnd) -> AntSystem {
+    let instance = tsplib::parse(instance).unwrap();
+    let props = AntProps {
+        rho: 0.9,
+        mode: Mode::ClosedTour,
+        dead_end,
+        seed: 3,
+        ..AntProps::new(instance.distances)
+    };
+
+    AntSystem::new(10, 0, props).unwrap()
+}
+
+#[test]
+fn missing_edges_are_infinite() {
+    let instance = tsplib::parse(RING).unwrap();
+    assert_eq!(instance.distances[[0, 3]], f64::INFINITY);
+    assert_eq!(instance.distances[[3, 0]], f64::INFINITY);
+    assert_eq!(instance.distances[[0, 2]], 1.0);
+}
+
+#[test]
+fn only_complete_tours_are_kept() {
+    for &dead_end in &[DeadEnd::Discard, DeadEnd::Backtrack, DeadEnd::Repair] {
+        let mut ant_system = ant_system(RING, dead_end);
+
+        for _ in 0..10 {
+            let solutions = ant_system.run(&mut NoopObserver).unwrap();
+            let stats = ant_system.history().iterations.last().unwrap();
+            assert_eq!(solutions.len() + stats.failed_ants, ant_system.size);
+
+            for (path, cost) in solutions {
+                let mut cities = path.clone();
+                cities.sort_unstable();
+                assert_eq!(cities, [0, 1, 2, 3, 4, 5], "{:?}", dead_end);
+                assert_eq!(cost, 6.0, "{:?} {:?}", dead_end, path);
+            }
+        }
+
+        if dead_end != DeadEnd::Discard {
+            assert_eq!(ant_system.best().unwrap().cost, 6.0, "{:?}", dead_end);
+        }
+    }
+}
+
+#[test]
+fn missing_edges_hold_no_pheromone() {
+    let mmas = Variant::MaxMin(MaxMinProps {
+        reinit_after: Some(2),
+        ..MaxMinProps::default()
+    });
+
+    for variant in &[Variant::AntSystem, mmas] {
+        let mut ant_system = ant_system(RING, DeadEnd::Repair);
+        ant_system.variant = variant.clone();
+
+        for _ in 0..10 {
+            ant_system.run(&mut NoopObserver).unwrap();
+
+            for ((r, c), &distance) in ant_system.distances.indexed_iter() {
+                if !distance.is_finite() {
+                    assert_eq!(ant_system.pheromones[[r, c]], 0.0, "{:?}", variant);
+                }
+            }
+
+            // Every city has at most three edges.
+            let stats = ant_system.history().iterations.last().unwrap();
+            assert!(stats.pheromone_min > 0.0, "{:?}", variant);
+            assert!(stats.branching_factor <= 3.0, "{:?}", variant);
+        }
+    }
+}
+
+#[test]
+fn no_improvement_fires_without_solutions() {
+    let mut ant_system = ant_system(LINE, DeadEnd::Repair);
+    let termination = Termination::NoImprovement(5);
+
+    let fired = ant_system
+        .run_until(&termination, &mut NoopObserver)
+        .unwrap();
+    assert_eq!(fired, termination);
+    assert_eq!(ant_system.iteration(), 5);
+    assert!(ant_system.best().is_none());
+}
@@ -0,0 +1,214 @@
+//! Properties every local search operator must keep on random Euclidean
+//! instances, in both modes.
+
+use ant_system::local_search::nearest_neighbours;
+use ant_system::{AntProps, AntSystem, LocalSearch, Mode, NoopObserver, Operator, Target};
+use ndarray::Array2;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+const CITIES: usize = 30;
+const EPSILON: f64 = 1e-9;
+
+fn random_instance(seed: u64) -> Array2<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let points: Vec<(f64, f64)> = (0..CITIES)
+        .map(|_| (rng.gen_range(0., 1000.), rng.gen_range(0., 1000.)))
+        .collect();
+
+    Array2::from_shape_fn((CITIES, CITIES), |(i, j)| {
+        let (xd, yd) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+        (xd * xd + yd * yd).sqrt()
+    })
+}
+
+/// A random path starting at city 0.
+fn random_path(seed: u64) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut path: Vec<usize> = (0..CITIES).collect();
+    path[1..].shuffle(&mut rng);
+    path
+}
+
+fn cost(path: &[usize], distances: &Array2<f64>, mode: Mode) -> f64 {
+    let mut cost: f64 = path
+        .windows(2)
+        .map(|edge| distances[[edge[0], edge[1]]])
+        .sum();
+    if mode == Mode::ClosedTour {
+        cost += distances[[path[path.len() - 1], path[0]]];
+    }
+    cost
+}
+
+fn local_search(operators: Vec<Operator>, neighbours: usize) -> LocalSearch {
+    LocalSearch {
+        operators,
+        target: Target::EveryAnt,
+        neighbours,
+    }
+}
+
+#[test]
+fn operators_keep_a_permutation_and_never_worsen() {
+    let operators = [
+        vec![Operator::TwoOpt],
+        vec![Operator::OrOpt],
+        vec![Operator::ThreeOpt],
+        vec![Operator::LinKernighan],
+        vec![Operator::TwoOpt, Operator::OrOpt, Operator::ThreeOpt],
+    ];
+
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        for seed in 0..5 {
+            let distances = random_instance(seed);
+            let neighbours = nearest_neighbours(&distances, 8);
+
+            for operators in &operators {
+                let mut path = random_path(seed);
+                let before = cost(&path, &distances, mode);
+                local_search(operators.clone(), 8).improve(
+                    &mut path,
+                    &distances,
+                    &neighbours,
+                    mode,
+                );
+
+                let mut cities = path.clone();
+                cities.sort_unstable();
+                assert!(cities.iter().cloned().eq(0..CITIES), "{:?}", operators);
+                assert_eq!(path[0], 0, "{:?} {:?}", mode, operators);
+                assert!(
+                    cost(&path, &distances, mode) <= before + EPSILON,
+                    "{:?} {:?}",
+                    mode,
+                    operators
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn operators_terminate_on_sparse_instances() {
+    let operators = [
+        vec![Operator::TwoOpt],
+        vec![Operator::OrOpt],
+        vec![Operator::ThreeOpt],
+        vec![Operator::LinKernighan],
+        vec![
+            Operator::TwoOpt,
+            Operator::OrOpt,
+            Operator::ThreeOpt,
+            Operator::LinKernighan,
+        ],
+    ];
+
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        for seed in 0..5 {
+            // Drop most edges, random paths then use many missing ones and
+            // neighbour lists hold some, so gains turn infinite or NaN.
+            let mut distances = random_instance(seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            for i in 0..CITIES {
+                for j in i + 1..CITIES {
+                    if rng.gen_bool(0.7) {
+                        distances[[i, j]] = f64::INFINITY;
+                        distances[[j, i]] = f64::INFINITY;
+                    }
+                }
+            }
+            let neighbours = nearest_neighbours(&distances, 8);
+
+            for operators in &operators {
+                let mut path = random_path(seed);
+                let before = cost(&path, &distances, mode);
+                local_search(operators.clone(), 8).improve(
+                    &mut path,
+                    &distances,
+                    &neighbours,
+                    mode,
+                );
+
+                let mut cities = path.clone();
+                cities.sort_unstable();
+                assert!(cities.iter().cloned().eq(0..CITIES), "{:?}", operators);
+                assert_eq!(path[0], 0, "{:?} {:?}", mode, operators);
+                let after = cost(&path, &distances, mode);
+                assert!(
+                    !before.is_finite() || after <= before + EPSILON,
+                    "{:?} {:?}",
+                    mode,
+                    operators
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn two_opt_leaves_no_improving_reversal() {
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        for seed in 0..5 {
+            let distances = random_instance(seed);
+            let neighbours = nearest_neighbours(&distances, CITIES - 1);
+            let mut path = random_path(seed);
+            local_search(vec![Operator::TwoOpt], CITIES - 1).improve(
+                &mut path,
+                &distances,
+                &neighbours,
+                mode,
+            );
+
+            // Every 2-opt move reverses a segment that doesn't hold the first
+            // city, in an open path that includes the segment ending the path.
+            let optimum = cost(&path, &distances, mode);
+            for i in 1..CITIES {
+                for j in i + 1..CITIES {
+                    let mut reversed = path.clone();
+                    reversed[i..=j].reverse();
+                    assert!(
+                        cost(&reversed, &distances, mode) >= optimum - EPSILON,
+                        "{:?} seed {}: reversing {}..={} improves {:?}",
+                        mode,
+                        seed,
+                        i,
+                        j,
+                        path
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn local_search_can_be_set_after_construction() {
+    let props = AntProps {
+        mode: Mode::ClosedTour,
+        ..AntProps::new(random_instance(0))
+    };
+    let mut ant_system = AntSystem::new(5, 0, props).unwrap();
+    ant_system.run(&mut NoopObserver).unwrap();
+
+    for &neighbours in &[5, 10] {
+        ant_system.local_search = Some(local_search(vec![Operator::TwoOpt], neighbours));
+        let distances = ant_system.distances.clone();
+        let lists = nearest_neighbours(&distances, neighbours);
+
+        for (path, cost) in ant_system.run(&mut NoopObserver).unwrap() {
+            // The colony used lists of the new length, improving further
+            // with them changes nothing.
+            let mut improved = path.clone();
+            local_search(vec![Operator::TwoOpt], neighbours).improve(
+                &mut improved,
+                &distances,
+                &lists,
+                Mode::ClosedTour,
+            );
+            assert_eq!(improved, path);
+            assert!((cost - self::cost(&path, &distances, Mode::ClosedTour)).abs() < EPSILON);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! The pheromone update deposits along the edges of every tour, these tests
+//! compare it against the original update that scanned every cell of the
+//! matrix looking for the ants that traveled it, in either direction unless
+//! the problem is asymmetric.
+
+use ant_system::{tsplib, AntProps, AntSystem, ElitistProps, Mode, NoopObserver, Variant};
+use ndarray::Array2;
+
+fn edges(path: &[usize], mode: Mode) -> Vec<(usize, usize)> {
+    let mut edges: Vec<_> = path.windows(2).map(|edge| (edge[0], edge[1])).collect();
+    if mode == Mode::ClosedTour && path.len() > 1 {
+        edges.push((path[path.len() - 1], path[0]));
+    }
+
+    edges
+}
+
+/// The full matrix update as it was first written.
+fn full_matrix_update(
+    pheromones: &Array2<f64>,
+    rho: f64,
+    depositors: &[(Vec<usize>, f64)],
+    mode: Mode,
+    asymmetric: bool,
+) -> Array2<f64> {
+    let mut pheromones = pheromones.clone();
+    let shape = pheromones.shape().to_owned();
+    let depositors: Vec<_> = depositors
+        .iter()
+        .map(|(path, amount)| (edges(path, mode), *amount))
+        .collect();
+
+    for r in 0..shape[0] {
+        for c in 0..shape[1] {
+            pheromones[[r, c]] *= rho;
+
+            for (edges, amount) in &depositors {
+                let traveled = edges.contains(&(r, c)) || (!asymmetric && edges.contains(&(c, r)));
+                pheromones[[r, c]] += if traveled { *amount } else { 0.0 };
+            }
+        }
+    }
+
+    pheromones
+}
+
+fn ant_system(mode: Mode, variant: Variant, asymmetric: bool) -> AntSystem {
+    let instance = tsplib::load("instances/example.tsp").unwrap();
+    let mut distances = instance.distances;
+    if asymmetric {
+        // Going towards a higher city costs more than coming back.
+        for ((r, c), distance) in distances.indexed_iter_mut() {
+            if r < c {
+                *distance += c as f64;
+            }
+        }
+    }
+
+    let props = AntProps {
+        beta: 2.0,
+        rho: 0.9,
+        mode,
+        asymmetric,
+        variant,
+        seed: 7,
+        ..AntProps::new(distances)
+    };
+
+    AntSystem::new(10, 0, props).unwrap()
+}
+
+#[test]
+fn ant_system_matches_full_matrix_update() {
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        let mut ant_system = ant_system(mode, Variant::AntSystem, false);
+
+        for _ in 0..20 {
+            let before = ant_system.pheromones.clone();
+            let solutions = ant_system.run(&mut NoopObserver).unwrap();
+
+            let depositors: Vec<_> = solutions
+                .into_iter()
+                .map(|(path, cost)| (path, ant_system.q / cost))
+                .collect();
+
+            let expected = full_matrix_update(&before, ant_system.rho, &depositors, mode, false);
+            assert_eq!(ant_system.pheromones, expected);
+        }
+    }
+}
+
+#[test]
+fn elitist_matches_full_matrix_update() {
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        let variant = Variant::Elitist(ElitistProps { e: 10.0 });
+        let mut ant_system = ant_system(mode, variant, false);
+
+        for _ in 0..20 {
+            let before = ant_system.pheromones.clone();
+            let solutions = ant_system.run(&mut NoopObserver).unwrap();
+            let best = ant_system.best().unwrap().clone();
+
+            let mut depositors: Vec<_> = solutions
+                .into_iter()
+                .map(|(path, cost)| (path, ant_system.q / cost))
+                .collect();
+            depositors.push((best.path, 10.0 * ant_system.q / best.cost));
+
+            let expected = full_matrix_update(&before, ant_system.rho, &depositors, mode, false);
+            assert_eq!(ant_system.pheromones, expected);
+        }
+    }
+}
+
+#[test]
+fn asymmetric_deposits_follow_the_direction() {
+    for &mode in &[Mode::OpenPath, Mode::ClosedTour] {
+        let mut ant_system = ant_system(mode, Variant::AntSystem, true);
+
+        for _ in 0..20 {
+            let before = ant_system.pheromones.clone();
+            let solutions = ant_system.run(&mut NoopObserver).unwrap();
+
+            let depositors: Vec<_> = solutions
+                .into_iter()
+                .map(|(path, cost)| (path, ant_system.q / cost))
+                .collect();
+
+            let expected = full_matrix_update(&before, ant_system.rho, &depositors, mode, true);
+            assert_eq!(ant_system.pheromones, expected);
+        }
+
+        assert_ne!(ant_system.pheromones, ant_system.pheromones.t());
+    }
+}
@@ -0,0 +1,78 @@
+//! A seed fixes the whole run: every ant draws from its own generator seeded
+//! by the colony, so building the solutions in parallel changes nothing.
+
+use ant_system::{AntProps, AntSystem, MaxMinProps, Mode, NoopObserver, Variant};
+use ndarray::Array2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CITIES: usize = 25;
+const SEED: u64 = 7;
+
+fn random_instance(seed: u64) -> Array2<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let points: Vec<(f64, f64)> = (0..CITIES)
+        .map(|_| (rng.gen_range(0., 1000.), rng.gen_range(0., 1000.)))
+        .collect();
+
+    Array2::from_shape_fn((CITIES, CITIES), |(i, j)| {
+        let (xd, yd) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+        (xd * xd + yd * yd).sqrt()
+    })
+}
+
+fn ant_system(variant: Variant, parallel: bool) -> AntSystem {
+    let props = AntProps {
+        mode: Mode::ClosedTour,
+        variant,
+        parallel,
+        seed: SEED,
+        ..AntProps::new(random_instance(0))
+    };
+
+    AntSystem::new(10, 0, props).unwrap()
+}
+
+fn variants() -> Vec<Variant> {
+    vec![Variant::AntSystem, Variant::MaxMin(MaxMinProps::default())]
+}
+
+/// Runs both colonies side by side, checking they agree after every
+/// iteration.
+fn assert_same_runs(mut a: AntSystem, mut b: AntSystem, variant: &Variant) {
+    for iteration in 1..=20 {
+        let solutions_a = a.run(&mut NoopObserver).unwrap();
+        let solutions_b = b.run(&mut NoopObserver).unwrap();
+        assert_eq!(
+            solutions_a, solutions_b,
+            "{:?} iteration {}",
+            variant, iteration
+        );
+        assert_eq!(
+            a.pheromones, b.pheromones,
+            "{:?} iteration {}",
+            variant, iteration
+        );
+        assert_eq!(a.best(), b.best(), "{:?} iteration {}", variant, iteration);
+    }
+}
+
+#[test]
+fn parallel_construction_matches_sequential() {
+    for variant in variants() {
+        let sequential = ant_system(variant.clone(), false);
+        let parallel = ant_system(variant.clone(), true);
+        assert_same_runs(sequential, parallel, &variant);
+    }
+}
+
+#[test]
+fn same_seed_same_run() {
+    for variant in variants() {
+        for &parallel in &[false, true] {
+            let first = ant_system(variant.clone(), parallel);
+            let second = ant_system(variant.clone(), parallel);
+            assert_same_runs(first, second, &variant);
+        }
+    }
+}
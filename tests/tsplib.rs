@@ -0,0 +1,65 @@
+use ant_system::tsplib::{self, Instance};
+
+fn coords(weight_type: &str, nodes: &str) -> Result<Instance, anyhow::Error> {
+    let dimension = nodes.lines().count();
+    tsplib::parse(&format!(
+        "NAME : coords
+TYPE : TSP
+DIMENSION : {}
+EDGE_WEIGHT_TYPE : {}
+NODE_COORD_SECTION
+{}
+EOF",
+        dimension, weight_type, nodes
+    ))
+}
+
+fn distances(weight_type: &str, nodes: &str) -> Vec<f64> {
+    let instance = coords(weight_type, nodes).unwrap();
+    (1..instance.dimension)
+        .map(|city| instance.distances[[0, city]])
+        .collect()
+}
+
+#[test]
+fn coordinate_distances() {
+    // 5, √2 rounded down, 2.5 rounded up.
+    assert_eq!(
+        distances("EUC_2D", "1 0 0\n2 3 4\n3 1 1\n4 1.5 2"),
+        [5.0, 1.0, 3.0]
+    );
+    assert_eq!(distances("CEIL_2D", "1 0 0\n2 3 4\n3 1 1"), [5.0, 2.0]);
+    // √10 = 3.16 rounds to 3 and is bumped to 4, √250 = 15.81 rounds to 16.
+    assert_eq!(distances("ATT", "1 0 0\n2 10 0\n3 30 40"), [4.0, 16.0]);
+    // One degree and 30 minutes (0.30) of longitude along the equator,
+    // 111.32 and 55.66 km plus one, truncated.
+    assert_eq!(distances("GEO", "1 0 0\n2 0 1\n3 0 0.30"), [112.0, 56.0]);
+}
+
+#[test]
+fn node_coordinates_follow_their_numbers() {
+    let instance = coords("EUC_2D", "2 3 4\n1 0 0\n3 0 8").unwrap();
+    assert_eq!(instance.distances[[0, 1]], 5.0);
+    assert_eq!(instance.distances[[0, 2]], 8.0);
+
+    assert!(coords("EUC_2D", "1 0 0\n1 3 4\n3 0 8").is_err());
+    assert!(coords("EUC_2D", "1 0 0\n2 3 4\n4 0 8").is_err());
+    assert!(coords("EUC_2D", "0 0 0\n2 3 4\n3 0 8").is_err());
+}
+
+#[test]
+fn dimensions_below_two_are_rejected() {
+    for dimension in 0..2 {
+        let error = tsplib::parse(&format!(
+            "NAME : tiny
+TYPE : TSP
+DIMENSION : {}
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+EOF",
+            dimension
+        ));
+        assert!(error.unwrap_err().to_string().contains("too small"));
+    }
+}
@@ -0,0 +1,137 @@
+use ant_system::{AntProps, AntSystem, MaxMinProps, Mode, ValidationError, Variant};
+use ndarray::{arr2, Array2};
+
+fn props(distances: Array2<f64>) -> AntProps {
+    AntProps {
+        rho: 0.9,
+        mode: Mode::ClosedTour,
+        seed: 1,
+        ..AntProps::new(distances)
+    }
+}
+
+fn triangle() -> Array2<f64> {
+    arr2(&[[0.0, 2.0, 9.0], [2.0, 0.0, 6.0], [9.0, 6.0, 0.0]])
+}
+
+#[test]
+fn valid_props_build_a_colony() {
+    assert!(AntSystem::new(3, 2, props(triangle())).is_ok());
+}
+
+#[test]
+fn distance_matrix_errors_point_at_the_cell() {
+    let not_square = Array2::from_elem((2, 3), 1.0);
+    assert_eq!(
+        props(not_square).validate(3, 0),
+        Err(ValidationError::NotSquare {
+            rows: 2,
+            columns: 3
+        })
+    );
+
+    let mut zero = triangle();
+    zero[[2, 1]] = 0.0;
+    assert_eq!(
+        props(zero).validate(3, 0),
+        Err(ValidationError::ZeroDistance { from: 2, to: 1 })
+    );
+
+    let mut asymmetric = triangle();
+    asymmetric[[2, 0]] = 4.0;
+    assert_eq!(
+        props(asymmetric.clone()).validate(3, 0),
+        Err(ValidationError::Asymmetric { from: 0, to: 2 })
+    );
+    let atsp = AntProps {
+        asymmetric: true,
+        ..props(asymmetric)
+    };
+    assert_eq!(atsp.validate(3, 0), Ok(()));
+
+    let mut negative = triangle();
+    negative[[0, 2]] = -1.0;
+    assert_eq!(
+        props(negative).validate(3, 0),
+        Err(ValidationError::InvalidDistance {
+            from: 0,
+            to: 2,
+            distance: -1.0
+        })
+    );
+
+    let mut below = triangle();
+    below[[2, 1]] = -6.0;
+    assert_eq!(
+        props(below).validate(3, 0),
+        Err(ValidationError::InvalidDistance {
+            from: 2,
+            to: 1,
+            distance: -6.0
+        })
+    );
+
+    let mut nan = triangle();
+    nan[[1, 2]] = f64::NAN;
+    match props(nan).validate(3, 0) {
+        Err(ValidationError::InvalidDistance { from, to, distance }) => {
+            assert_eq!((from, to), (1, 2));
+            assert!(distance.is_nan());
+        }
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    let mut isolated = triangle();
+    for city in 0..2 {
+        isolated[[city, 2]] = f64::INFINITY;
+        isolated[[2, city]] = f64::INFINITY;
+    }
+    assert_eq!(
+        props(isolated).validate(3, 0),
+        Err(ValidationError::Isolated { city: 2 })
+    );
+
+    // The diagonal is never traveled.
+    let mut diagonal = triangle();
+    diagonal[[1, 1]] = f64::NAN;
+    assert_eq!(props(diagonal).validate(3, 0), Ok(()));
+}
+
+#[test]
+fn parameter_errors_name_the_parameter() {
+    assert_eq!(
+        props(triangle()).validate(3, 3),
+        Err(ValidationError::StartOutOfRange {
+            start: 3,
+            cities: 3
+        })
+    );
+    assert_eq!(
+        props(triangle()).validate(0, 0),
+        Err(ValidationError::NoAnts)
+    );
+
+    let with_rho = |rho, variant| {
+        let props = AntProps {
+            rho,
+            variant,
+            ..props(triangle())
+        };
+
+        props.validate(3, 0)
+    };
+
+    assert_eq!(with_rho(1.0, Variant::AntSystem), Ok(()));
+    for (rho, variant) in &[
+        (0.0, Variant::AntSystem),
+        (1.5, Variant::AntSystem),
+        (1.0, Variant::MaxMin(MaxMinProps::default())),
+    ] {
+        match with_rho(*rho, variant.clone()) {
+            Err(ValidationError::InvalidParameter { name, value, .. }) => {
+                assert_eq!((name, value), ("rho", *rho), "{:?}", variant)
+            }
+            other => panic!("Unexpected result {:?} for {:?}", other, variant),
+        }
+    }
+}